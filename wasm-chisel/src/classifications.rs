@@ -1,11 +1,161 @@
-use parity_wasm::elements::Instruction;
+use once_cell::sync::Lazy;
+use parity_wasm::elements::{BlockType, BrTableData, Instruction};
 use parity_wasm::elements::Instruction::*;
+#[cfg(feature = "sign_ext")]
+use parity_wasm::elements::SignExtInstruction;
+use std::collections::HashMap;
+use std::mem::{discriminant, Discriminant};
+
+/// The classification an instruction belongs to, one for each of the arrays below
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Category {
+	Get,
+	Control,
+	Call,
+	Parametric,
+	Set,
+	Const,
+	I32Testop,
+	I64Testop,
+	Load,
+	Store,
+	Memory,
+	I32Unop,
+	I64Unop,
+	I32Binop,
+	I32Relop,
+	I64Binop,
+	I64Relop,
+	F32Unop,
+	F64Unop,
+	F32Binop,
+	F64Binop,
+	F32Relop,
+	F64Relop,
+	Conversion,
+	/// Only instructions read with the `sign_ext` feature fall in it, but the variant is always there
+	/// so that enabling the feature in one crate doesn't break matches on `Category` in another
+	ExtendSign,
+}
+
+impl Category {
+	/// The name of the array holding the category's instructions
+	pub fn name(self) -> &'static str {
+		match self {
+			Category::Get => "GET_INST",
+			Category::Control => "CONTROL_INST",
+			Category::Call => "CALL_INST",
+			Category::Parametric => "PARAMETRIC_INST",
+			Category::Set => "SET_INST",
+			Category::Const => "CONST_INST",
+			Category::I32Testop => "I32_TESTOP",
+			Category::I64Testop => "I64_TESTOP",
+			Category::Load => "LOAD_INST",
+			Category::Store => "STORE_INST",
+			Category::Memory => "MEMORY_INST",
+			Category::I32Unop => "I32_UNOP",
+			Category::I64Unop => "I64_UNOP",
+			Category::I32Binop => "I32_BINOP",
+			Category::I32Relop => "I32_RELOP",
+			Category::I64Binop => "I64_BINOP",
+			Category::I64Relop => "I64_RELOP",
+			Category::F32Unop => "F32_UNOP",
+			Category::F64Unop => "F64_UNOP",
+			Category::F32Binop => "F32_BINOP",
+			Category::F64Binop => "F64_BINOP",
+			Category::F32Relop => "F32_RELOP",
+			Category::F64Relop => "F64_RELOP",
+			Category::Conversion => "CONVERSION_INST",
+			Category::ExtendSign => "EXTEND_SIGN",
+		}
+	}
+}
+
+/// Every classified instruction keyed by its discriminant, so classifying an instruction is a single lookup
+static CATEGORIES: Lazy<HashMap<Discriminant<Instruction>, Category>> = Lazy::new(|| {
+	let classifications: [(&[Instruction], Category); 24] = [
+		(&GET_INST, Category::Get),
+		(&CONTROL_INST, Category::Control),
+		(&CALL_INST, Category::Call),
+		(&PARAMETRIC_INST, Category::Parametric),
+		(&SET_INST, Category::Set),
+		(&CONST_INST, Category::Const),
+		(&I32_TESTOP, Category::I32Testop),
+		(&I64_TESTOP, Category::I64Testop),
+		(&LOAD_INST, Category::Load),
+		(&STORE_INST, Category::Store),
+		(&MEMORY_INST, Category::Memory),
+		(&I32_UNOP, Category::I32Unop),
+		(&I64_UNOP, Category::I64Unop),
+		(&I32_BINOP, Category::I32Binop),
+		(&I32_RELOP, Category::I32Relop),
+		(&I64_BINOP, Category::I64Binop),
+		(&I64_RELOP, Category::I64Relop),
+		(&F32_UNOP, Category::F32Unop),
+		(&F64_UNOP, Category::F64Unop),
+		(&F32_BINOP, Category::F32Binop),
+		(&F64_BINOP, Category::F64Binop),
+		(&F32_RELOP, Category::F32Relop),
+		(&F64_RELOP, Category::F64Relop),
+		(&CONVERSION_INST, Category::Conversion),
+	];
+
+	let mut categories = HashMap::new();
+	let mut add = |instructions: &[Instruction], category: Category| {
+		for instruction in instructions.iter() {
+			categories.insert(discriminant(instruction), category);
+		}
+	};
+	for (instructions, category) in classifications.iter() {
+		add(instructions, *category);
+	}
+	// br_table owns its list of targets, which can't be built in a const array
+	add(&[BrTable(Box::new(BrTableData { table: Box::new([]), default: 0 }))], Category::Control);
+	// the proposals' instructions only exist when parity-wasm is built with them
+	#[cfg(feature = "sign_ext")]
+	add(&EXTEND_SIGN, Category::ExtendSign);
+	categories
+});
+
+/// Looks up which classification an instruction belongs to, ignoring its immediates
+pub fn classify(instruction: &Instruction) -> Option<Category> {
+	CATEGORIES.get(&discriminant(instruction)).copied()
+}
 
 pub const GET_INST: [Instruction; 2] = [
 	GetGlobal(0),
 	GetLocal(0),
 ];
 
+pub const CONTROL_INST: [Instruction; 10] = [
+	Unreachable,
+	Nop,
+	Block(BlockType::NoResult),
+	Loop(BlockType::NoResult),
+	If(BlockType::NoResult),
+	Else,
+	End,
+	Br(0),
+	BrIf(0),
+	Return,
+];
+
+pub const CALL_INST: [Instruction; 2] = [
+	Call(0),
+	CallIndirect(0, 0),
+];
+
+pub const PARAMETRIC_INST: [Instruction; 2] = [
+	Drop,
+	Select,
+];
+
+pub const SET_INST: [Instruction; 3] = [
+	SetGlobal(0),
+	SetLocal(0),
+	TeeLocal(0),
+];
+
 pub const CONST_INST: [Instruction; 4] = [
 	I32Const(0),
 	I64Const(0),
@@ -13,9 +163,59 @@ pub const CONST_INST: [Instruction; 4] = [
 	F64Const(0),
 ];
 
-// pub const I32_TESTOP: [Instruction; 1] = [
-// 	I32Eqz,
-// ];
+pub const I32_TESTOP: [Instruction; 1] = [
+	I32Eqz,
+];
+
+pub const I64_TESTOP: [Instruction; 1] = [
+	I64Eqz,
+];
+
+pub const LOAD_INST: [Instruction; 14] = [
+	I32Load(0, 0),
+	I64Load(0, 0),
+	F32Load(0, 0),
+	F64Load(0, 0),
+	I32Load8S(0, 0),
+	I32Load8U(0, 0),
+	I32Load16S(0, 0),
+	I32Load16U(0, 0),
+	I64Load8S(0, 0),
+	I64Load8U(0, 0),
+	I64Load16S(0, 0),
+	I64Load16U(0, 0),
+	I64Load32S(0, 0),
+	I64Load32U(0, 0),
+];
+
+pub const STORE_INST: [Instruction; 9] = [
+	I32Store(0, 0),
+	I64Store(0, 0),
+	F32Store(0, 0),
+	F64Store(0, 0),
+	I32Store8(0, 0),
+	I32Store16(0, 0),
+	I64Store8(0, 0),
+	I64Store16(0, 0),
+	I64Store32(0, 0),
+];
+
+pub const MEMORY_INST: [Instruction; 2] = [
+	CurrentMemory(0),
+	GrowMemory(0),
+];
+
+pub const I32_UNOP: [Instruction; 3] = [
+	I32Clz,
+	I32Ctz,
+	I32Popcnt,
+];
+
+pub const I64_UNOP: [Instruction; 3] = [
+	I64Clz,
+	I64Ctz,
+	I64Popcnt,
+];
 
 pub const I32_BINOP: [Instruction; 15] = [
     I32Add,
@@ -35,6 +235,19 @@ pub const I32_BINOP: [Instruction; 15] = [
 	I32Rotr,
 ];
 
+pub const I32_RELOP: [Instruction; 10] = [
+	I32Eq,
+	I32Ne,
+	I32LtS,
+	I32LtU,
+	I32GtS,
+	I32GtU,
+	I32LeS,
+	I32LeU,
+	I32GeS,
+	I32GeU,
+];
+
 pub const I64_BINOP: [Instruction; 15] = [
 	I64Add,
 	I64Sub,
@@ -53,6 +266,39 @@ pub const I64_BINOP: [Instruction; 15] = [
 	I64Rotr
 ];
 
+pub const I64_RELOP: [Instruction; 10] = [
+	I64Eq,
+	I64Ne,
+	I64LtS,
+	I64LtU,
+	I64GtS,
+	I64GtU,
+	I64LeS,
+	I64LeU,
+	I64GeS,
+	I64GeU,
+];
+
+pub const F32_UNOP: [Instruction; 7] = [
+	F32Abs,
+	F32Neg,
+	F32Ceil,
+	F32Floor,
+	F32Trunc,
+	F32Nearest,
+	F32Sqrt,
+];
+
+pub const F64_UNOP: [Instruction; 7] = [
+	F64Abs,
+	F64Neg,
+	F64Ceil,
+	F64Floor,
+	F64Trunc,
+	F64Nearest,
+	F64Sqrt,
+];
+
 pub const F32_BINOP: [Instruction; 7] = [
 	F32Add,
 	F32Sub,
@@ -71,4 +317,61 @@ pub const F64_BINOP: [Instruction; 7] = [
 	F64Min,
 	F64Max,
 	F64Copysign,
-];
\ No newline at end of file
+];
+
+pub const F32_RELOP: [Instruction; 6] = [
+	F32Eq,
+	F32Ne,
+	F32Lt,
+	F32Gt,
+	F32Le,
+	F32Ge,
+];
+
+pub const F64_RELOP: [Instruction; 6] = [
+	F64Eq,
+	F64Ne,
+	F64Lt,
+	F64Gt,
+	F64Le,
+	F64Ge,
+];
+
+/// Reinterpretations are included, `get_conversion_signature` gives the types of each
+pub const CONVERSION_INST: [Instruction; 25] = [
+	I32WrapI64,
+	I32TruncSF32,
+	I32TruncUF32,
+	I32TruncSF64,
+	I32TruncUF64,
+	I64ExtendSI32,
+	I64ExtendUI32,
+	I64TruncSF32,
+	I64TruncUF32,
+	I64TruncSF64,
+	I64TruncUF64,
+	F32ConvertSI32,
+	F32ConvertUI32,
+	F32ConvertSI64,
+	F32ConvertUI64,
+	F32DemoteF64,
+	F64ConvertSI32,
+	F64ConvertUI32,
+	F64ConvertSI64,
+	F64ConvertUI64,
+	F64PromoteF32,
+	I32ReinterpretF32,
+	I64ReinterpretF64,
+	F32ReinterpretI32,
+	F64ReinterpretI64,
+];
+
+/// All of these share a single `SignExt` discriminant, `get_sign_ext_signature` tells them apart
+#[cfg(feature = "sign_ext")]
+pub const EXTEND_SIGN: [Instruction; 5] = [
+	SignExt(SignExtInstruction::I32Extend8S),
+	SignExt(SignExtInstruction::I32Extend16S),
+	SignExt(SignExtInstruction::I64Extend8S),
+	SignExt(SignExtInstruction::I64Extend16S),
+	SignExt(SignExtInstruction::I64Extend32S),
+];
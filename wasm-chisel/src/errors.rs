@@ -1,45 +1,327 @@
-use parity_wasm::elements::Instruction;
+use crate::features::Feature;
+use crate::value_type_name;
+use parity_wasm::elements::{self, ValueType};
 use std::error;
 use std::fmt;
+use std::io;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum InstructionError {
 	GlobalNotFound,
 	LocalNotFound,
 	UnmatchedInstruction,
-	InvalidOperation(Instruction),
+	InvalidOperation {
+		#[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::value_type"))]
+		expected: ValueType,
+		#[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::value_type"))]
+		found: ValueType,
+	},
+	ResultMismatch {
+		#[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::value_types"))]
+		expected: Vec<ValueType>,
+		#[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::value_types"))]
+		found: Vec<ValueType>,
+	},
+	StackUnderflow,
+	ImmutableGlobal(u32),
+	NoMemory,
+	InvalidBranchTarget(u32),
+	FunctionNotFound(u32),
+	TypeNotFound(u32),
+	NoTable,
+	StackOverflow(usize),
+	MalformedModule,
+	BadAlignment { align: u32, natural: u32 },
+	InvalidStartFunction(u32),
+	InvalidExport(String),
+	InvalidInitExpr(u32),
+	InvalidDataSegment(usize),
+	InvalidElementSegment(usize),
+	UnhandledInstruction(String),
+	TooManyLocals,
+	ForbiddenInstruction(String),
+	InvalidLimits,
+	TooManyMemories,
+	TooManyTables,
+	LimitExceeded(Limit),
+	BranchTargetMismatch {
+		#[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::value_types"))]
+		expected: Vec<ValueType>,
+		#[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::value_types"))]
+		got: Vec<ValueType>,
+		target: u32,
+	},
+	NonEmptyStack {
+		#[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::value_types"))]
+		leftover: Vec<ValueType>,
+	},
+	FeatureDisabled(Feature),
+	SectionLengthMismatch { functions: usize, bodies: usize },
+	InvalidImport { index: usize },
+	UnreachableCode,
+	InvalidTableType,
+	NotInFunction,
 }
 
 impl fmt::Display for InstructionError {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
 			InstructionError::GlobalNotFound =>
-				write!(f, "Global not found"),
+				write!(f, "global not found"),
 			InstructionError::LocalNotFound =>
-				write!(f, "Local not found"),
+				write!(f, "local not found"),
 			InstructionError::UnmatchedInstruction =>
-				write!(f, "Unmatched instruction"),
-			InstructionError::InvalidOperation(i) =>
-				write!(f, "{}", format!("Invalid operation: {:?}", i).as_str()),
+				write!(f, "unmatched instruction"),
+			InstructionError::InvalidOperation { expected, found } =>
+				write!(f, "invalid operation: expected {}, found {}", expected, found),
+			InstructionError::ResultMismatch { expected, found } =>
+				write!(f, "result mismatch: expected {}, found {}", TypeList(expected), TypeList(found)),
+			InstructionError::StackUnderflow =>
+				write!(f, "stack underflow"),
+			InstructionError::ImmutableGlobal(index) =>
+				write!(f, "global {} is immutable", index),
+			InstructionError::NoMemory =>
+				write!(f, "no memory declared"),
+			InstructionError::InvalidBranchTarget(depth) =>
+				write!(f, "invalid branch target: {}", depth),
+			InstructionError::FunctionNotFound(index) =>
+				write!(f, "function {} not found", index),
+			InstructionError::TypeNotFound(index) =>
+				write!(f, "type {} not found", index),
+			InstructionError::NoTable =>
+				write!(f, "no table declared"),
+			InstructionError::StackOverflow(limit) =>
+				write!(f, "stack holds more than {} values", limit),
+			InstructionError::MalformedModule =>
+				write!(f, "malformed module"),
+			InstructionError::BadAlignment { align, natural } =>
+				write!(f, "alignment 2^{} is larger than the natural alignment 2^{}", align, natural),
+			InstructionError::InvalidStartFunction(index) =>
+				write!(f, "start function {} must take no parameters and return no results", index),
+			InstructionError::InvalidExport(name) =>
+				write!(f, "export `{}` refers to an entity that doesn't exist", name),
+			InstructionError::InvalidInitExpr(index) =>
+				write!(f, "global {} is not initialized with a constant of its type", index),
+			InstructionError::InvalidDataSegment(index) =>
+				write!(f, "data segment {} needs an existing memory and a constant i32 offset", index),
+			InstructionError::InvalidElementSegment(index) =>
+				write!(f, "element segment {} needs an existing table, a constant i32 offset and existing functions", index),
+			InstructionError::UnhandledInstruction(instruction) =>
+				write!(f, "{} is not supported by the validator", instruction),
+			InstructionError::TooManyLocals =>
+				write!(f, "function declares more than 2^32 - 1 locals"),
+			InstructionError::ForbiddenInstruction(instruction) =>
+				write!(f, "{} is not allowed", instruction),
+			InstructionError::InvalidLimits =>
+				write!(f, "memory or table limits are invalid"),
+			InstructionError::TooManyMemories =>
+				write!(f, "more than one memory declared"),
+			InstructionError::TooManyTables =>
+				write!(f, "more than one table declared"),
+			InstructionError::LimitExceeded(limit) =>
+				write!(f, "too many {}", limit),
+			InstructionError::BranchTargetMismatch { expected, got, target } =>
+				write!(f, "branch target {} takes {}, but the default target takes {}", target, TypeList(got), TypeList(expected)),
+			InstructionError::NonEmptyStack { leftover } =>
+				write!(f, "function without results leaves {} on the stack", TypeList(leftover)),
+			InstructionError::FeatureDisabled(feature) =>
+				write!(f, "{} instructions are not enabled", feature),
+			InstructionError::SectionLengthMismatch { functions, bodies } =>
+				write!(f, "function section declares {} functions, but the code section has {} bodies", functions, bodies),
+			InstructionError::InvalidImport { index } =>
+				write!(f, "import {} refers to a missing type or has invalid limits", index),
+			InstructionError::UnreachableCode =>
+				write!(f, "unreachable code"),
+			InstructionError::InvalidTableType =>
+				write!(f, "call_indirect needs a table of functions"),
+			InstructionError::NotInFunction =>
+				write!(f, "instruction needs an enclosing function"),
 		}
 	}
 }
 
-impl error::Error for InstructionError {
-	fn description(&self) -> &str {
+impl error::Error for InstructionError {}
+
+/// Writes value types the way the text format names them, e.g. `[i32, f64]`
+struct TypeList<'a>(&'a [ValueType]);
+
+impl fmt::Display for TypeList<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let names: Vec<&str> = self.0.iter().map(|value_type| value_type_name(*value_type)).collect();
+		write!(f, "[{}]", names.join(", "))
+	}
+}
+
+/// A limit configured on the validator to protect against modules from untrusted sources
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Limit {
+	Functions,
+	Locals,
+	Instructions,
+}
+
+impl fmt::Display for Limit {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
-			InstructionError::GlobalNotFound =>
-				"Global not found",
-			InstructionError::LocalNotFound =>
-				"Local not found",
-			InstructionError::UnmatchedInstruction =>
-				"Unmatched instruction",
-			InstructionError::InvalidOperation(_) =>
-				"Invalid operation"
+			Limit::Functions => write!(f, "functions"),
+			Limit::Locals => write!(f, "locals"),
+			Limit::Instructions => write!(f, "instructions"),
+		}
+	}
+}
+
+/// An `InstructionError` along with where in the module it was found
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LocatedError {
+	/// The index of the function in the code section, `None` for problems with the module as a whole
+	pub function_index: Option<usize>,
+	/// The position of the offending instruction within the function body, `None` for problems with the module as a whole
+	pub instruction_index: Option<usize>,
+	pub error: InstructionError,
+	/// The operand stack just before the offending instruction ran, bottom first
+	#[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::value_types"))]
+	pub stack_before: Vec<ValueType>,
+	/// The function's name from the module's `name` section, if it has one
+	pub function_name: Option<String>,
+	/// The name of the local the offending instruction accesses, if it has one
+	pub local_name: Option<String>,
+	/// Where the offending instruction starts in the module's binary, if it could be worked out
+	pub byte_offset: Option<usize>,
+}
+
+impl LocatedError {
+	pub fn new(function_index: usize, instruction_index: usize, error: InstructionError) -> Self {
+		LocatedError {
+			function_index: Some(function_index),
+			instruction_index: Some(instruction_index),
+			..LocatedError::module(error)
+		}
+	}
+
+	/// An error found outside of the function bodies, which isn't tied to any instruction
+	pub fn module(error: InstructionError) -> Self {
+		LocatedError {
+			function_index: None,
+			instruction_index: None,
+			error,
+			stack_before: vec![],
+			function_name: None,
+			local_name: None,
+			byte_offset: None,
 		}
 	}
 
-	fn cause(&self) -> Option<&error::Error> {
-		None
+	/// Attaches the operand stack as it was before the offending instruction
+	pub fn with_stack(mut self, stack_before: Vec<ValueType>) -> Self {
+		self.stack_before = stack_before;
+		self
+	}
+
+	/// Attaches the names of the function and local involved, as found in the module's `name` section
+	pub fn with_names(mut self, function_name: Option<String>, local_name: Option<String>) -> Self {
+		self.function_name = function_name;
+		self.local_name = local_name;
+		self
+	}
+
+	/// Attaches where the offending instruction starts in the module's binary
+	pub fn with_byte_offset(mut self, byte_offset: Option<usize>) -> Self {
+		self.byte_offset = byte_offset;
+		self
+	}
+}
+
+impl fmt::Display for LocatedError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.function_index {
+			Some(index) => write!(f, "function {}", index)?,
+			None => write!(f, "module")?,
+		}
+		if let Some(name) = &self.function_name {
+			write!(f, " (`{}`)", name)?;
+		}
+		if let Some(index) = self.instruction_index {
+			write!(f, ", instruction {}", index)?;
+		}
+		if let Some(name) = &self.local_name {
+			write!(f, ", local `{}`", name)?;
+		}
+		write!(f, ": {}", self.error)
+	}
+}
+
+impl error::Error for LocatedError {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		Some(&self.error)
+	}
+}
+
+/// Any error that can stop a module from being validated, including failing to read it in the first place
+#[derive(Debug)]
+pub enum ValidationError {
+	/// The module could not be read
+	Io(io::Error),
+	/// The bytes could not be deserialized into a module
+	Deserialize(elements::Error),
+	/// The text format could not be assembled into a module
+	#[cfg(feature = "wat")]
+	Wat(wat::Error),
+	/// The module was read but one of its instructions is invalid
+	Instruction(LocatedError),
+}
+
+impl fmt::Display for ValidationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ValidationError::Io(e) =>
+				write!(f, "failed to read module: {}", e),
+			ValidationError::Deserialize(e) =>
+				write!(f, "failed to deserialize module: {}", e),
+			#[cfg(feature = "wat")]
+			ValidationError::Wat(e) =>
+				write!(f, "failed to assemble module: {}", e),
+			ValidationError::Instruction(e) =>
+				write!(f, "{}", e),
+		}
+	}
+}
+
+impl error::Error for ValidationError {
+	fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+		match self {
+			ValidationError::Io(e) => Some(e),
+			ValidationError::Deserialize(e) => Some(e),
+			#[cfg(feature = "wat")]
+			ValidationError::Wat(e) => Some(e),
+			ValidationError::Instruction(e) => Some(e),
+		}
+	}
+}
+
+impl From<io::Error> for ValidationError {
+	fn from(error: io::Error) -> Self {
+		ValidationError::Io(error)
+	}
+}
+
+impl From<elements::Error> for ValidationError {
+	fn from(error: elements::Error) -> Self {
+		ValidationError::Deserialize(error)
+	}
+}
+
+#[cfg(feature = "wat")]
+impl From<wat::Error> for ValidationError {
+	fn from(error: wat::Error) -> Self {
+		ValidationError::Wat(error)
+	}
+}
+
+impl From<LocatedError> for ValidationError {
+	fn from(error: LocatedError) -> Self {
+		ValidationError::Instruction(error)
 	}
 }
@@ -0,0 +1,56 @@
+use std::fmt;
+
+/// A WebAssembly proposal beyond the MVP that instructions can belong to.
+/// Only proposals parity-wasm can read are listed.
+/// Reference types are missing since parity-wasm has no instructions or value types for them yet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Feature {
+	/// `i32.extend8_s` and the other sign-extension operators
+	SignExt,
+	/// 128-bit vector instructions
+	Simd,
+}
+
+impl fmt::Display for Feature {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Feature::SignExt => write!(f, "sign-extension"),
+			Feature::Simd => write!(f, "simd"),
+		}
+	}
+}
+
+/// The proposals a target runtime supports, so instructions from any other one can be rejected
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeatureSet {
+	pub sign_ext: bool,
+	pub simd: bool,
+}
+
+impl FeatureSet {
+	/// Only what the MVP allows
+	pub fn mvp() -> Self {
+		FeatureSet { sign_ext: false, simd: false }
+	}
+
+	/// Every proposal the validator knows about
+	pub fn all() -> Self {
+		FeatureSet { sign_ext: true, simd: true }
+	}
+
+	/// Whether instructions from `feature` are allowed
+	pub fn contains(&self, feature: Feature) -> bool {
+		match feature {
+			Feature::SignExt => self.sign_ext,
+			Feature::Simd => self.simd,
+		}
+	}
+}
+
+impl Default for FeatureSet {
+	/// Everything parity-wasm can read is allowed unless a runtime says otherwise
+	fn default() -> Self {
+		FeatureSet::all()
+	}
+}
@@ -1,12 +1,28 @@
+// errors carry the stack and names to make failures easy to diagnose, and are only built once validation fails
+#![allow(clippy::result_large_err)]
+
+use bitflags::bitflags;
+use once_cell::sync::OnceCell;
 use parity_wasm::elements::*;
 
 mod classifications;
 mod errors;
+mod features;
+mod report;
+mod rules;
+#[cfg(feature = "serde")]
+mod serialize;
 
 use crate::classifications::*;
-use crate::errors::*;
-use self::Filter::*;
-use std::mem::discriminant;
+pub use crate::classifications::Category;
+pub use crate::errors::{InstructionError, Limit, LocatedError, ValidationError};
+pub use crate::features::{Feature, FeatureSet};
+pub use crate::report::{FunctionReport, ValidationReport};
+pub use crate::rules::{InstructionRule, NoFloat};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::path::Path;
 
 /*
  * TODO:
@@ -21,6 +37,9 @@ type Pop = Vec<ValueType>;
 /// Type alias representing the values pushed onto the stack by a given operation.
 /// Mostly just for readability
 type Push = Vec<ValueType>;
+/// A run of locals of one type, as the index just past its last local and their type.
+/// Keeping where each run ends lets a local be found with a binary search.
+type LocalGroup = (u64, ValueType);
 
 /// The "signature" of a type, meaning the values pushed and popped from the stack by the operation.
 struct Signature {
@@ -28,35 +47,274 @@ struct Signature {
 	push: Push
 }
 
-/// An enum representing which instructions should be validated
-pub enum Filter {
-	NumericInstructions,
-	NoFilter
+/// The kind of structured instruction that opened a control frame
+enum BlockKind {
+	Block,
+	Loop,
+	If,
+	/// An `if` whose `else` arm has been reached
+	Else,
+	/// The function body itself, the outermost block
+	Function,
+}
+
+/// A block opened by `block`, `loop` or `if`, tracked until its matching `end`
+struct ControlFrame {
+	kind: BlockKind,
+	/// The height of the operand stack when the block was entered
+	height: usize,
+	/// The values the block leaves on the stack once it ends
+	result: Vec<ValueType>,
+	/// Whether the enclosing block was already unreachable, restored once this block ends
+	enclosing_polymorphic: bool,
+}
+
+bitflags! {
+	/// The families of instructions that should be validated.
+	/// Local, global and parametric instructions are always checked, since every family depends on them.
+	pub struct Filter: u32 {
+		/// Constants and the integer and float unary, binary, test and comparison operators
+		const NUMERIC = 0b0001;
+		/// Loads, stores, `current_memory` and `grow_memory`
+		const MEMORY = 0b0010;
+		/// Blocks, branches, calls and the function's own result
+		const CONTROL = 0b0100;
+		/// Conversions and reinterpretations between value types
+		const CONVERSION = 0b1000;
+	}
+}
+
+impl Filter {
+	/// Only the numeric instructions, see `Filter::NUMERIC`
+	pub fn numeric() -> Self {
+		Filter::NUMERIC
+	}
+}
+
+impl Default for Filter {
+	/// Validates every family, the broadest check
+	fn default() -> Self {
+		Filter::all()
+	}
 }
 
 /// Basic struct for validating modules
 pub struct ModuleValidator<'a> {
 	module: &'a Module,
 	filter: Filter,
-	stack: Vec<ValueType>
+	/// The most values the operand stack may hold at once, if limited
+	max_stack_height: Option<usize>,
+	/// Whether instructions the validator doesn't know how to model are rejected instead of skipped
+	strict: bool,
+	/// Whether reports list the instructions that can never run
+	warn_unreachable: bool,
+	/// Whether validation fails on warnings as if they were errors
+	warnings_as_errors: bool,
+	/// Whether the module is held to the MVP's limit of one memory and one table
+	mvp_strict: bool,
+	/// The most function bodies the module may have, if limited
+	max_functions: Option<usize>,
+	/// The most locals, parameters included, a function may have, if limited
+	max_locals_per_function: Option<usize>,
+	/// The most instructions a function body may have, if limited
+	max_instructions_per_function: Option<usize>,
+	/// The proposals instructions may come from
+	features: FeatureSet,
+	/// Extra checks registered by the caller, run on every instruction
+	rules: Vec<Box<dyn InstructionRule>>,
+	/// The binary the module was read from, if the caller has it
+	source: Option<&'a [u8]>,
+	/// What the validator reads from the module only once an error needs it, shared with an `OwnedModuleValidator`
+	lazy: Cow<'a, LazyParts>,
+}
+
+/// The parts of a module that are only read to describe errors, worked out the first time one needs them
+#[derive(Clone, Default)]
+struct LazyParts {
+	/// The module's `name` section, used to name functions and locals in errors
+	names: OnceCell<Option<NameSection>>,
+	/// Where each instruction of each body starts in the binary
+	offsets: OnceCell<Option<Vec<Vec<usize>>>>,
+}
+
+/// The settings of a validator, kept apart from the module so an `OwnedModuleValidator` can hold them too
+#[derive(Clone)]
+struct Options {
+	filter: Filter,
+	max_stack_height: Option<usize>,
+	strict: bool,
+	warn_unreachable: bool,
+	warnings_as_errors: bool,
+	mvp_strict: bool,
+	max_functions: Option<usize>,
+	max_locals_per_function: Option<usize>,
+	max_instructions_per_function: Option<usize>,
+	features: FeatureSet,
+}
+
+impl Default for Options {
+	/// Validates everything with no limits
+	fn default() -> Self {
+		Options {
+			filter: Filter::all(),
+			max_stack_height: None,
+			strict: false,
+			warn_unreachable: false,
+			warnings_as_errors: false,
+			mvp_strict: false,
+			max_functions: None,
+			max_locals_per_function: None,
+			max_instructions_per_function: None,
+			features: FeatureSet::default(),
+		}
+	}
+}
+
+/// Builds a `ModuleValidator`, so new options don't widen `ModuleValidator::new`
+pub struct ModuleValidatorBuilder<'a> {
+	module: &'a Module,
+	options: Options,
+	source: Option<&'a [u8]>,
+}
+
+impl<'a> ModuleValidatorBuilder<'a> {
+
+	/// Starts a builder for the given module, validating everything with no stack limit by default
+	pub fn new(module: &'a Module) -> Self {
+		ModuleValidatorBuilder{
+			module,
+			options: Options::default(),
+			source: None,
+		}
+	}
+
+	/// Sets which instructions should be validated
+	pub fn filter(mut self, filter: Filter) -> Self {
+		self.options.filter = filter;
+		self
+	}
+
+	/// Rejects functions whose operand stack ever holds more than `height` values
+	pub fn max_stack_height(mut self, height: usize) -> Self {
+		self.options.max_stack_height = Some(height);
+		self
+	}
+
+	/// Rejects instructions the validator doesn't know how to model instead of skipping them
+	pub fn strict(mut self, strict: bool) -> Self {
+		self.options.strict = strict;
+		self
+	}
+
+	/// Lists the instructions that follow a `return`, branch or `unreachable` in reports, as warnings rather than errors
+	pub fn warn_unreachable(mut self, warn_unreachable: bool) -> Self {
+		self.options.warn_unreachable = warn_unreachable;
+		self
+	}
+
+	/// Makes validation fail on unreachable code, reporting the first instruction `warn_unreachable` would warn about as an error
+	pub fn warnings_as_errors(mut self, warnings_as_errors: bool) -> Self {
+		self.options.warnings_as_errors = warnings_as_errors;
+		self
+	}
+
+	/// Rejects modules with more than one memory or table, which only runtimes past the MVP support
+	pub fn mvp_strict(mut self, mvp_strict: bool) -> Self {
+		self.options.mvp_strict = mvp_strict;
+		self
+	}
+
+	/// Rejects modules with more than `count` function bodies
+	pub fn max_functions(mut self, count: usize) -> Self {
+		self.options.max_functions = Some(count);
+		self
+	}
+
+	/// Rejects functions with more than `count` locals, parameters included
+	pub fn max_locals_per_function(mut self, count: usize) -> Self {
+		self.options.max_locals_per_function = Some(count);
+		self
+	}
+
+	/// Rejects function bodies with more than `count` instructions
+	pub fn max_instructions_per_function(mut self, count: usize) -> Self {
+		self.options.max_instructions_per_function = Some(count);
+		self
+	}
+
+	/// Rejects instructions from proposals outside of `features`
+	pub fn features(mut self, features: FeatureSet) -> Self {
+		self.options.features = features;
+		self
+	}
+
+	/// The bytes the module was deserialized from, so errors give byte offsets into exactly those bytes
+	pub fn source(mut self, bytes: &'a [u8]) -> Self {
+		self.source = Some(bytes);
+		self
+	}
+
+	/// Creates the configured validator
+	pub fn build(self) -> ModuleValidator<'a> {
+		ModuleValidator::with_options(self.module, &self.options, self.source, Cow::Owned(LazyParts::default()))
+	}
+
+	/// Creates the configured validator around a copy of the module, so it can be stored without a lifetime
+	pub fn build_owned(self) -> OwnedModuleValidator {
+		OwnedModuleValidator {
+			module: self.module.clone(),
+			options: self.options,
+			source: self.source.map(|bytes| bytes.to_vec()),
+			lazy: LazyParts::default(),
+		}
+	}
 }
 
 impl<'a> ModuleValidator<'a> {
 
 	/// Convenience method for creating a new validator
 	pub fn new(module: &'a Module, filter: Filter) -> Self {
-		ModuleValidator{ module, filter, stack: vec![] }
+		ModuleValidatorBuilder::new(module).filter(filter).build()
+	}
+
+	/// Creates a validator with the given settings, reading names and offsets into `lazy`
+	fn with_options(module: &'a Module, options: &Options, source: Option<&'a [u8]>, lazy: Cow<'a, LazyParts>) -> Self {
+		ModuleValidator{
+			module,
+			filter: options.filter,
+			max_stack_height: options.max_stack_height,
+			strict: options.strict,
+			warn_unreachable: options.warn_unreachable,
+			warnings_as_errors: options.warnings_as_errors,
+			mvp_strict: options.mvp_strict,
+			max_functions: options.max_functions,
+			max_locals_per_function: options.max_locals_per_function,
+			max_instructions_per_function: options.max_instructions_per_function,
+			features: options.features,
+			rules: vec![],
+			source,
+			lazy,
+		}
+	}
+
+	/// Registers a rule that every instruction has to pass, in addition to the built-in checks
+	pub fn add_rule(&mut self, rule: Box<dyn InstructionRule>) {
+		self.rules.push(rule);
 	}
 
 	/// Handler method that loops over functions and delegates validation to `check_instructions`
-	pub fn validate(&mut self) -> Result<bool, InstructionError> {
+	pub fn validate(&self) -> Result<bool, LocatedError> {
+		self.validate_module().map_err(LocatedError::module)?;
 		match self.module.code_section() {
 			Some(functions) => {
 				for (index, function) in functions.bodies().iter().enumerate() {
-					let is_function_valid: bool = self.check_instructions(function, index)?;
+					let (is_function_valid, unreachable) = self.check_instructions(function, index)?;
 					if !is_function_valid {
 						return Ok(false)
 					}
+					if let Some(position) = unreachable {
+						return Err(LocatedError::new(index, position, InstructionError::UnreachableCode))
+					}
 				}
 				Ok(true)
 			},
@@ -64,156 +322,5042 @@ impl<'a> ModuleValidator<'a> {
 		}
 	}
 
-	/// A method used to determine what the classification of each instruction, and execute the correct method on it
-	fn check_instructions(&mut self, body: &FuncBody, index: usize) -> Result<bool, InstructionError> {
-		for instruction in body.code().elements() {
-			if contains(instruction, &GET_INST) && !self.push_global_or_local(instruction, body, index)? {
-					return Ok(false)
-			}
-			match self.filter {
-				NumericInstructions => {
-					let signature = get_instruction_signature(instruction);
-					// if the instruction does not have a signature we are interested in, we continue
-					if signature.is_some() && !self.validate_instruction(&signature.unwrap(), instruction)? {
-						return Ok(false)
-					}					
-				}
-				NoFilter => () // TODO: do this
-			};
-		}
-		Ok(true)
+	/// Checks the parts of the module outside of function bodies
+	pub fn validate_module(&self) -> Result<(), InstructionError> {
+		let bodies = self.module.code_section().map_or(0, |section| section.bodies().len());
+		if self.max_functions.is_some_and(|limit| bodies > limit) {
+			return Err(InstructionError::LimitExceeded(Limit::Functions))
+		}
+		self.check_section_lengths()?;
+		self.check_imports()?;
+		self.check_start()?;
+		self.check_exports()?;
+		self.check_globals()?;
+		self.check_data_segments()?;
+		self.check_element_segments()?;
+		self.check_limits()?;
+		if self.mvp_strict {
+			self.check_mvp_counts()?;
+		}
+		Ok(())
+	}
+
+	/// Validates the module and writes the outcome of every function to `w`, one line per result or error
+	pub fn report_to<W: Write>(&self, w: &mut W) -> io::Result<bool> {
+		let report = self.validate_report();
+		if let Some(error) = &report.module_error {
+			writeln!(w, "{}", error)?;
+		}
+		for function in &report.per_function {
+			if function.valid {
+				writeln!(w, "function {}: ok", function.function_index)?;
+			}
+			for error in &function.errors {
+				writeln!(w, "{}", error)?;
+			}
+			for position in &function.unreachable {
+				writeln!(w, "function {}, instruction {}: warning: unreachable code", function.function_index, position)?;
+			}
+		}
+		Ok(report.valid)
+	}
+
+	/// Validates only the function whose body is at `index` in the code section
+	pub fn validate_function(&self, index: usize) -> Result<bool, InstructionError> {
+		let body = self.module.code_section()
+			.and_then(|section| section.bodies().get(index))
+			.ok_or(InstructionError::FunctionNotFound(index as u32))?;
+		self.check_instructions(body, index)
+			.map(|(is_valid, _)| is_valid)
+			.map_err(|located| located.error)
+	}
+
+	/// Checks a run of instructions on top of an `initial` stack and returns the stack they leave behind.
+	/// The run belongs to no function, so it can't access locals, `return` or branch out of itself.
+	pub fn validate_sequence(&self, instructions: &[Instruction], initial: Vec<ValueType>) -> Result<Vec<ValueType>, InstructionError> {
+		let state = &mut FunctionState::new(None);
+		state.stack = initial;
+		for (position, instruction) in instructions.iter().enumerate() {
+			state.begin_instruction(position);
+			self.check_instruction(state, instruction, &[])?;
+		}
+		Ok(state.stack.clone())
+	}
+
+	/// Like `validate`, but keeps going after an error so that every problem in the module is reported.
+	/// An empty list means the module is valid.
+	pub fn validate_all(&self) -> Vec<LocatedError> {
+		self.errors().collect()
+	}
+
+	/// Lazily yields every error in the module, in order, starting with any problem outside of the function bodies.
+	/// Each function body is only checked once the errors of the ones before it have been consumed.
+	pub fn errors(&self) -> impl Iterator<Item = LocatedError> + '_ {
+		let module_error = self.validate_module().err().map(LocatedError::module);
+		let bodies = self.module.code_section().map_or(&[][..], |section| section.bodies());
+		module_error.into_iter().chain(bodies.iter().enumerate()
+			.flat_map(move |(index, function)| self.report_function(function, index).errors))
+	}
+
+	/// Like `validate_all`, but also reports how much of the module was checked and groups the errors by function
+	pub fn validate_report(&self) -> ValidationReport {
+		let module_error = self.validate_module().err().map(LocatedError::module);
+		let mut per_function = vec![];
+		let mut instructions_checked = 0;
+		let mut category_counts = HashMap::new();
+		if let Some(functions) = self.module.code_section() {
+			for (index, function) in functions.bodies().iter().enumerate() {
+				per_function.push(self.report_function(function, index));
+				instructions_checked += function.code().elements().len();
+				for category in function.code().elements().iter().filter_map(classify) {
+					*category_counts.entry(category.name().to_string()).or_insert(0) += 1;
+				}
+			}
+		}
+		ValidationReport::new(module_error, per_function, instructions_checked, category_counts)
+	}
+
+	/// Lists the classifications of every instruction in the module without type-checking anything.
+	/// Instructions outside of every classification are left out.
+	pub fn classify(&self) -> HashSet<Category> {
+		let bodies = self.module.code_section().map_or(&[][..], |section| section.bodies());
+		bodies.iter()
+			.flat_map(|body| body.code().elements())
+			.filter_map(classify)
+			.collect()
+	}
+
+	/// Checks that every declared function has a body and every body a declaration
+	fn check_section_lengths(&self) -> Result<(), InstructionError> {
+		let functions = self.module.function_section().map_or(0, |section| section.entries().len());
+		let bodies = self.module.code_section().map_or(0, |section| section.bodies().len());
+		if functions != bodies {
+			return Err(InstructionError::SectionLengthMismatch { functions, bodies })
+		}
+		Ok(())
+	}
+
+	/// Checks that imported functions refer to existing types and imported memories and tables have valid limits
+	fn check_imports(&self) -> Result<(), InstructionError> {
+		let types = self.module.type_section().map_or(0, |section| section.types().len());
+		for (index, external) in self.imports().enumerate() {
+			let valid = match external {
+				External::Function(type_ref) => (*type_ref as usize) < types,
+				External::Memory(memory) => memory_limits_valid(memory.limits()),
+				External::Table(table) => limits_ordered(table.limits()),
+				External::Global(_) => true,
+			};
+			if !valid {
+				return Err(InstructionError::InvalidImport { index })
+			}
+		}
+		Ok(())
+	}
+
+	/// Checks that the start function, if there is one, takes no parameters and returns no results
+	fn check_start(&self) -> Result<(), InstructionError> {
+		let index = match self.module.start_section() {
+			Some(index) => index,
+			None => return Ok(()),
+		};
+		let function_type = self.resolve_function_type(index)
+			.ok_or(InstructionError::FunctionNotFound(index))?;
+		if !function_type.params().is_empty() || !results(function_type).is_empty() {
+			return Err(InstructionError::InvalidStartFunction(index))
+		}
+		Ok(())
+	}
+
+	/// Checks that every export refers to an entity that exists, counting imports
+	fn check_exports(&self) -> Result<(), InstructionError> {
+		let exports = self.module.export_section().map_or(&[][..], |section| section.entries());
+		for export in exports {
+			let (index, count) = match export.internal() {
+				Internal::Function(index) => (index, self.function_count()),
+				Internal::Table(index) => (index, self.table_count()),
+				Internal::Memory(index) => (index, self.memory_count()),
+				Internal::Global(index) => (index, self.global_count()),
+			};
+			if *index as usize >= count {
+				return Err(InstructionError::InvalidExport(export.field().to_string()))
+			}
+		}
+		Ok(())
+	}
+
+	/// Checks that every global defined in the module is initialized with a constant of its own type
+	fn check_globals(&self) -> Result<(), InstructionError> {
+		// defined globals come after the imported ones in the index space
+		let imported = self.imports().filter(|external| matches!(external, External::Global(_))).count();
+		let globals = self.module.global_section().map_or(&[][..], |section| section.entries());
+		for (position, global) in globals.iter().enumerate() {
+			if self.init_expr_type(global.init_expr()) != Some(global.global_type().content_type()) {
+				return Err(InstructionError::InvalidInitExpr((imported + position) as u32))
+			}
+		}
+		Ok(())
+	}
+
+	/// Checks that every data segment is placed in a memory that exists, at a constant `i32` offset
+	fn check_data_segments(&self) -> Result<(), InstructionError> {
+		let segments = self.module.data_section().map_or(&[][..], |section| section.entries());
+		for (position, segment) in segments.iter().enumerate() {
+			// passive segments have no offset to check
+			let offset_valid = segment.offset().as_ref().is_none_or(|expr| self.init_expr_type(expr) == Some(ValueType::I32));
+			if !offset_valid || segment.index() as usize >= self.memory_count() {
+				return Err(InstructionError::InvalidDataSegment(position))
+			}
+		}
+		Ok(())
+	}
+
+	/// Checks that every element segment is placed in a table that exists, at a constant `i32` offset,
+	/// and only refers to functions that exist
+	fn check_element_segments(&self) -> Result<(), InstructionError> {
+		let segments = self.module.elements_section().map_or(&[][..], |section| section.entries());
+		let function_count = self.function_count();
+		for (position, segment) in segments.iter().enumerate() {
+			// passive segments have no offset to check
+			let offset_valid = segment.offset().as_ref().is_none_or(|expr| self.init_expr_type(expr) == Some(ValueType::I32));
+			let members_valid = segment.members().iter().all(|member| (*member as usize) < function_count);
+			if !offset_valid || !members_valid || segment.index() as usize >= self.table_count() {
+				return Err(InstructionError::InvalidElementSegment(position))
+			}
+		}
+		Ok(())
+	}
+
+	/// Checks that every memory and table the module defines has a maximum no smaller than its minimum,
+	/// and that memories stay within the 4GiB a 32-bit address can reach
+	fn check_limits(&self) -> Result<(), InstructionError> {
+		let memories = self.module.memory_section().map_or(&[][..], |section| section.entries());
+		if !memories.iter().all(|memory| memory_limits_valid(memory.limits())) {
+			return Err(InstructionError::InvalidLimits)
+		}
+		let tables = self.module.table_section().map_or(&[][..], |section| section.entries());
+		if !tables.iter().all(|table| limits_ordered(table.limits())) {
+			return Err(InstructionError::InvalidLimits)
+		}
+		Ok(())
+	}
+
+	/// Checks that the module has at most one memory and one table, counting imports, as the MVP requires
+	fn check_mvp_counts(&self) -> Result<(), InstructionError> {
+		if self.memory_count() > 1 {
+			return Err(InstructionError::TooManyMemories)
+		}
+		if self.table_count() > 1 {
+			return Err(InstructionError::TooManyTables)
+		}
+		Ok(())
+	}
+
+	/// The type of the value a constant expression produces, or `None` if it isn't a constant expression.
+	/// Only constants and immutable imported globals may appear, since nothing else is known before the module runs.
+	fn init_expr_type(&self, expr: &InitExpr) -> Option<ValueType> {
+		match expr.code() {
+			[instruction, Instruction::End] => match instruction {
+				Instruction::I32Const(_) => Some(ValueType::I32),
+				Instruction::I64Const(_) => Some(ValueType::I64),
+				Instruction::F32Const(_) => Some(ValueType::F32),
+				Instruction::F64Const(_) => Some(ValueType::F64),
+				Instruction::GetGlobal(index) => {
+					let mut imported = self.imports().filter_map(|external| match external {
+						External::Global(global_type) => Some(global_type),
+						_ => None,
+					});
+					match imported.nth(*index as usize) {
+						Some(global_type) if !global_type.is_mutable() => Some(global_type.content_type()),
+						_ => None,
+					}
+				},
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+
+	/// Validates a whole function body, stopping at the first error.
+	/// When warnings are errors, also returns the position of the first instruction that can never run.
+	fn check_instructions(&self, body: &FuncBody, index: usize) -> Result<(bool, Option<usize>), LocatedError> {
+		let mut unreachable = None;
+		let state = &mut FunctionState::new(Some(index));
+		let locals = self.prepare_function(state, body, index).map_err(|error| self.locate(state, error))?;
+		for (position, instruction) in body.code().elements().iter().enumerate() {
+			state.begin_instruction(position);
+			// same as `report_function`, `end` and `else` close the dead part of a block rather than belong to it
+			let closes_block = matches!(instruction, Instruction::End | Instruction::Else);
+			if self.warnings_as_errors && state.polymorphic && unreachable.is_none() && !closes_block {
+				unreachable = Some(position);
+			}
+			let is_valid = self.check_instruction(state, instruction, &locals)
+				.map_err(|error| self.locate(state, error))?;
+			if !is_valid {
+				return Ok((false, None))
+			}
+		}
+		// the body's own `end` checks the result, so this only catches bodies built without one
+		if !state.control_stack.is_empty() {
+			state.begin_instruction(last_position(body));
+			self.check_function_result(state)
+				.map_err(|error| self.locate(state, error))?;
+		}
+		Ok((true, unreachable))
+	}
+
+	/// Validates a whole function body, recording every error instead of stopping at the first one
+	fn report_function(&self, body: &FuncBody, index: usize) -> FunctionReport {
+		let mut errors = vec![];
+		let mut unreachable = vec![];
+		let state = &mut FunctionState::new(Some(index));
+		let locals = match self.prepare_function(state, body, index) {
+			Ok(locals) => locals,
+			Err(error) => {
+				errors.push(self.locate(state, error));
+				return FunctionReport { function_index: index, valid: false, errors, unreachable }
+			},
+		};
+		for (position, instruction) in body.code().elements().iter().enumerate() {
+			state.begin_instruction(position);
+			// errors make the rest of their block unreachable too, so dead code is only trusted before the first one.
+			// `end` and `else` close the dead part of a block rather than belong to it.
+			let closes_block = matches!(instruction, Instruction::End | Instruction::Else);
+			let warn = self.warn_unreachable || self.warnings_as_errors;
+			if warn && state.polymorphic && errors.is_empty() && !closes_block {
+				unreachable.push(position);
+			}
+			if let Err(error) = self.check_instruction(state, instruction, &locals) {
+				errors.push(self.locate(state, error));
+				// the stack can't be trusted after an error, so type the rest of the block
+				// as if it were unreachable rather than reporting the same problem over and over
+				state.set_unreachable();
+			}
+		}
+		// the body's own `end` checks the result, so this only catches bodies built without one
+		if !state.control_stack.is_empty() {
+			state.begin_instruction(last_position(body));
+			if let Err(error) = self.check_function_result(state) {
+				errors.push(self.locate(state, error));
+			}
+		}
+		// dead code is only listed before the first error, so it goes first, where `validate` would have stopped
+		if let (true, Some(&position)) = (self.warnings_as_errors, unreachable.first()) {
+			errors.insert(0, LocatedError::new(index, position, InstructionError::UnreachableCode));
+		}
+		FunctionReport { function_index: index, valid: errors.is_empty(), errors, unreachable }
+	}
+
+	/// Wraps an error with where it was raised and what the stack held before the offending instruction
+	fn locate(&self, state: &FunctionState, error: InstructionError) -> LocatedError {
+		let located = LocatedError {
+			function_index: state.function_index,
+			instruction_index: Some(state.instruction_index),
+			..LocatedError::module(error)
+		};
+		located.with_stack(state.stack_before())
+			.with_names(self.function_name(state), self.local_name(state))
+			.with_byte_offset(self.byte_offset(state))
+	}
+
+	/// Works out where the instruction currently being checked starts in the module's binary.
+	/// parity-wasm doesn't keep offsets around, so they are read from the source bytes if the validator has them,
+	/// or else from the module serialized again, which matches the original as long as it had no padding.
+	fn byte_offset(&self, state: &FunctionState) -> Option<usize> {
+		let offsets = self.lazy.offsets.get_or_init(|| match self.source {
+			Some(bytes) => instruction_offsets(bytes),
+			None => serialize(self.module.clone()).ok().and_then(|bytes| instruction_offsets(&bytes)),
+		});
+		offsets.as_ref()?.get(state.function_index?)?.get(state.instruction_index).copied()
+	}
+
+	/// The name of the function currently being checked, if the module has one for it
+	fn function_name(&self, state: &FunctionState) -> Option<String> {
+		let index = self.module.import_count(ImportCountType::Function) + state.function_index?;
+		self.names()?.functions()?.names().get(index as u32).cloned()
+	}
+
+	/// The name of the local accessed by the instruction currently being checked, if it accesses one that has a name
+	fn local_name(&self, state: &FunctionState) -> Option<String> {
+		let body = self.module.code_section()?.bodies().get(state.function_index?)?;
+		let local = match body.code().elements().get(state.instruction_index)? {
+			Instruction::GetLocal(local) | Instruction::SetLocal(local) | Instruction::TeeLocal(local) => *local,
+			_ => return None,
+		};
+		let index = self.module.import_count(ImportCountType::Function) + state.function_index?;
+		self.names()?.locals()?.local_names().get(index as u32)?.get(local).cloned()
+	}
+
+	/// The module's `name` section, read the first time an error is named
+	fn names(&self) -> Option<&NameSection> {
+		self.lazy.names.get_or_init(|| read_names(self.module)).as_ref()
+	}
+
+	/// Checks an instruction and then runs the rules on the operands it actually took and left
+	fn check_instruction(&self, state: &mut FunctionState, instruction: &Instruction, locals: &[LocalGroup]) -> Result<bool, InstructionError> {
+		let is_valid = self.apply_instruction(state, instruction, locals)?;
+		if is_valid && !self.rules.is_empty() {
+			let popped = state.popped_operands();
+			for rule in &self.rules {
+				rule.check_effect(instruction, &popped, state.pushed_operands())?;
+			}
+		}
+		Ok(is_valid)
+	}
+
+	/// A method used to determine what the classification of each instruction, and execute the correct method on it
+	fn apply_instruction(&self, state: &mut FunctionState, instruction: &Instruction, locals: &[LocalGroup]) -> Result<bool, InstructionError> {
+		if let Some(feature) = instruction_feature(instruction) {
+			if !self.features.contains(feature) {
+				return Err(InstructionError::FeatureDisabled(feature))
+			}
+		}
+		// rules are the caller's own policy, so they apply whatever the filter skips
+		for rule in &self.rules {
+			rule.check(instruction, &state.stack)?;
+		}
+		if !self.filter.contains(instruction_family(instruction)) {
+			self.skip_instruction(state, instruction)?;
+			self.check_stack_height(state)?;
+			return Ok(true)
+		}
+		if is_category(instruction, Category::Get) && !self.push_global_or_local(state, instruction, locals)? {
+			return Ok(false)
+		}
+		if is_category(instruction, Category::Set) && !self.pop_global_or_local(state, instruction, locals)? {
+			return Ok(false)
+		}
+		if is_control(instruction) && !self.validate_control(state, instruction)? {
+			return Ok(false)
+		}
+		if is_category(instruction, Category::Call) && !self.validate_call(state, instruction)? {
+			return Ok(false)
+		}
+		if is_category(instruction, Category::Parametric) && !state.validate_parametric(instruction)? {
+			return Ok(false)
+		}
+		if uses_memory(instruction) && !self.has_memory() {
+			return Err(InstructionError::NoMemory)
+		}
+		if let Some((align, natural)) = get_alignment(instruction) {
+			if align > natural {
+				return Err(InstructionError::BadAlignment { align, natural })
+			}
+		}
+		// if the instruction does not have a signature we are interested in, we continue
+		if let Some(signature) = get_instruction_signature(instruction) {
+			if !state.validate_instruction(&signature)? {
+				return Ok(false)
+			}
+		} else if self.strict && !is_modeled(instruction) {
+			return Err(InstructionError::UnhandledInstruction(instruction.to_string()))
+		}
+		// checking once the instruction is done covers every path that pushes onto the stack
+		self.check_stack_height(state)?;
+		Ok(true)
+	}
+
+	/// Checks that the operand stack hasn't grown past the configured limit
+	fn check_stack_height(&self, state: &FunctionState) -> Result<(), InstructionError> {
+		match self.max_stack_height {
+			Some(limit) if state.stack.len() > limit => Err(InstructionError::StackOverflow(limit)),
+			_ => Ok(()),
+		}
+	}
+
+	/// Checks that the values left on the stack at the end of the body match the function's results
+	fn check_function_result(&self, state: &FunctionState) -> Result<(), InstructionError> {
+		// whatever is left on the stack is what the function returns
+		let expected = results(self.get_function_type(state.function()?)?);
+		// unreachable code may have consumed results that were never pushed, so only the known part has to match
+		if state.stack != expected && !(state.polymorphic && expected.ends_with(&state.stack)) {
+			// a function without results that leaves values behind is a bug of its own, so it gets its own error
+			if expected.is_empty() {
+				return Err(InstructionError::NonEmptyStack { leftover: state.stack.clone() })
+			}
+			return Err(InstructionError::ResultMismatch { expected, found: state.stack.clone() })
+		}
+		Ok(())
+	}
+
+	/// Handles the control instructions, which affect how the rest of the block is typed
+	fn validate_control(&self, state: &mut FunctionState, instruction: &Instruction) -> Result<bool, InstructionError> {
+		match instruction {
+			Instruction::Unreachable => {
+				state.set_unreachable();
+				Ok(true)
+			},
+			Instruction::Nop => Ok(true),
+			Instruction::Block(block_type) => {
+				state.push_frame(BlockKind::Block, *block_type);
+				Ok(true)
+			},
+			Instruction::Loop(block_type) => {
+				state.push_frame(BlockKind::Loop, *block_type);
+				Ok(true)
+			},
+			Instruction::If(block_type) => {
+				state.pop_expected(ValueType::I32)?;
+				state.push_frame(BlockKind::If, *block_type);
+				Ok(true)
+			},
+			Instruction::Else => {
+				let mut frame = match state.control_stack.pop() {
+					Some(frame) => frame,
+					None => return Err(InstructionError::UnmatchedInstruction),
+				};
+				match frame.kind {
+					BlockKind::If => frame.kind = BlockKind::Else,
+					_ => return Err(InstructionError::UnmatchedInstruction),
+				}
+				state.check_block_results(&frame.result, frame.height)?;
+
+				// the else arm starts over from the stack the if was entered with
+				state.truncate_stack(frame.height);
+				state.polymorphic = false;
+				state.control_stack.push(frame);
+				Ok(true)
+			},
+			Instruction::Br(depth) => {
+				let label = self.get_label_types(state, *depth)?;
+				state.check_results(&label, state.current_height())?;
+				// nothing after an unconditional branch runs
+				state.set_unreachable();
+				Ok(true)
+			},
+			Instruction::BrIf(depth) => {
+				state.pop_expected(ValueType::I32)?;
+				// the label values stay on the stack in case the branch is not taken
+				let label = self.get_label_types(state, *depth)?;
+				state.check_results(&label, state.current_height())?;
+				Ok(true)
+			},
+			Instruction::BrTable(data) => {
+				state.pop_expected(ValueType::I32)?;
+				// every target receives the same values, so they all have to agree with the default
+				let label = self.get_label_types(state, data.default)?;
+				for depth in data.table.iter() {
+					let target = self.get_label_types(state, *depth)?;
+					if target != label {
+						return Err(InstructionError::BranchTargetMismatch { expected: label, got: target, target: *depth })
+					}
+				}
+				state.check_results(&label, state.current_height())?;
+				state.set_unreachable();
+				Ok(true)
+			},
+			Instruction::Return => {
+				let expected = results(self.get_function_type(state.function()?)?);
+				state.check_results(&expected, state.current_height())?;
+				state.set_unreachable();
+				Ok(true)
+			},
+			Instruction::End => {
+				let frame = match state.control_stack.pop() {
+					Some(frame) => frame,
+					None => return Err(InstructionError::UnmatchedInstruction),
+				};
+				match frame.kind {
+					// the function body's own end, where whatever is left on the stack is returned
+					BlockKind::Function => self.check_function_result(state)?,
+					_ => state.check_block_results(&frame.result, frame.height)?,
+				}
+				// an if without an else produces nothing when the condition is false
+				if let BlockKind::If = frame.kind {
+					if !frame.result.is_empty() {
+						return Err(InstructionError::ResultMismatch { expected: frame.result, found: vec![] })
+					}
+				}
+
+				// the block's operands are gone, only its results remain for the enclosing block
+				state.truncate_stack(frame.height);
+				state.stack.extend(&frame.result);
+				state.polymorphic = frame.enclosing_polymorphic;
+				Ok(true)
+			},
+			_ => { Err(InstructionError::UnmatchedInstruction) },
+		}
+	}
+
+	/// Determines the types a branch to the label `depth` blocks out has to provide.
+	/// Branching to a loop jumps back to its start, which takes no values, while any other block is exited with its results.
+	/// The outermost label belongs to the function body itself, whose results are the function's.
+	fn get_label_types(&self, state: &FunctionState, depth: u32) -> Result<Vec<ValueType>, InstructionError> {
+		match state.control_stack.iter().rev().nth(depth as usize) {
+			Some(ControlFrame { kind: BlockKind::Loop, .. }) => Ok(vec![]),
+			Some(frame) => Ok(frame.result.clone()),
+			None => Err(InstructionError::InvalidBranchTarget(depth)),
+		}
+	}
+
+	/// Handles calls, which pop the callee's parameters and push its results
+	fn validate_call(&self, state: &mut FunctionState, instruction: &Instruction) -> Result<bool, InstructionError> {
+		if let Instruction::CallIndirect(_, _) = instruction {
+			// the MVP only has table 0, which has to hold functions for them to be called
+			let table = self.resolve_table(0).ok_or(InstructionError::NoTable)?;
+			if table.elem_type() != TableElementType::AnyFunc {
+				return Err(InstructionError::InvalidTableType)
+			}
+		}
+		state.validate_instruction(&self.get_call_signature(instruction)?)
+	}
+
+	/// Works out what a call pops and pushes from the callee's type.
+	/// Indirect calls also pop the index of the callee in the table.
+	fn get_call_signature(&self, instruction: &Instruction) -> Result<Signature, InstructionError> {
+		let (function_type, selector) = match instruction {
+			Instruction::Call(function) => {
+				match self.resolve_function_type(*function) {
+					Some(function_type) => (function_type, None),
+					None => return Err(InstructionError::FunctionNotFound(*function)),
+				}
+			},
+			Instruction::CallIndirect(type_ref, _) => {
+				let type_section = self.module.type_section().map_or(&[][..], |section| section.types());
+				match type_section.get(*type_ref as usize) {
+					// the index into the table is on top of the arguments
+					Some(Type::Function(function_type)) => (function_type, Some(ValueType::I32)),
+					None => return Err(InstructionError::TypeNotFound(*type_ref)),
+				}
+			},
+			_ => return Err(InstructionError::UnmatchedInstruction),
+		};
+
+		// the last parameter is on top of the stack, so it gets popped first
+		Ok(Signature {
+			pop: selector.into_iter().chain(function_type.params().iter().rev().cloned()).collect(),
+			push: results(function_type),
+		})
+	}
+
+	/// Applies the stack effect of an instruction whose family isn't being validated, without checking its operands.
+	/// Blocks are still tracked, so branches and `end` leave the stack as it would be at runtime
+	/// and the function's result is checked whatever the filter.
+	fn skip_instruction(&self, state: &mut FunctionState, instruction: &Instruction) -> Result<(), InstructionError> {
+		match instruction {
+			Instruction::Block(block_type) => state.push_frame(BlockKind::Block, *block_type),
+			Instruction::Loop(block_type) => state.push_frame(BlockKind::Loop, *block_type),
+			Instruction::If(block_type) => {
+				state.pop_operand();
+				state.push_frame(BlockKind::If, *block_type);
+			},
+			Instruction::Else => {
+				// the else arm starts over from the stack the if was entered with
+				let height = state.current_height();
+				state.truncate_stack(height);
+				state.polymorphic = false;
+			},
+			Instruction::End => {
+				match state.control_stack.pop() {
+					Some(ControlFrame { kind: BlockKind::Function, .. }) => self.check_function_result(state)?,
+					Some(frame) => {
+						state.truncate_stack(frame.height);
+						state.stack.extend(&frame.result);
+						state.polymorphic = frame.enclosing_polymorphic;
+					},
+					None => (),
+				}
+			},
+			Instruction::BrIf(_) => {
+				state.pop_operand();
+			},
+			Instruction::Br(_) | Instruction::BrTable(_) | Instruction::Return | Instruction::Unreachable => state.set_unreachable(),
+			Instruction::Call(_) | Instruction::CallIndirect(_, _) => {
+				// a callee that can't be found has no known effect, which is for the control checks to report
+				if let Ok(signature) = self.get_call_signature(instruction) {
+					state.apply_unchecked(&signature);
+				}
+			},
+			_ => {
+				if let Some(signature) = get_instruction_signature(instruction) {
+					state.apply_unchecked(&signature);
+				}
+			},
+		}
+		Ok(())
+	}
+
+	/// A method used to load global or local variable types onto the stack
+	fn push_global_or_local(&self, state: &mut FunctionState, instruction: &Instruction, locals: &[LocalGroup]) -> Result<bool, InstructionError> {
+		match instruction {
+			Instruction::GetGlobal(global) => {
+				match self.resolve_global(*global) {
+					Some(global_type) => {
+						state.stack.push(global_type.content_type());
+						Ok(true)
+					},
+					None => { Err(InstructionError::GlobalNotFound) },
+				}
+			},
+			Instruction::GetLocal(local) => {
+				match local_type(locals, *local) {
+					Some(value_type) => {
+						state.stack.push(value_type);
+						Ok(true)
+					},
+					None => { Err(InstructionError::LocalNotFound) },
+				}
+			},
+			_ => { Err(InstructionError::UnmatchedInstruction) },
+		}
+	}
+
+	/// A method used to store the value on top of the stack into a global or local variable, checking its type.
+	/// `tee_local` pushes the value back afterwards.
+	fn pop_global_or_local(&self, state: &mut FunctionState, instruction: &Instruction, locals: &[LocalGroup]) -> Result<bool, InstructionError> {
+		match instruction {
+			Instruction::SetGlobal(global) => {
+				let global_type = match self.resolve_global(*global) {
+					Some(global_type) => global_type,
+					None => return Err(InstructionError::GlobalNotFound),
+				};
+				if !global_type.is_mutable() {
+					return Err(InstructionError::ImmutableGlobal(*global))
+				}
+				state.pop_expected(global_type.content_type())?;
+				Ok(true)
+			},
+			Instruction::SetLocal(local) | Instruction::TeeLocal(local) => {
+				let local_type = match local_type(locals, *local) {
+					Some(value_type) => value_type,
+					None => return Err(InstructionError::LocalNotFound),
+				};
+				state.pop_expected(local_type)?;
+				// tee_local leaves the stored value on the stack for the next instruction
+				if let Instruction::TeeLocal(_) = instruction {
+					state.stack.push(local_type);
+				}
+				Ok(true)
+			},
+			_ => { Err(InstructionError::UnmatchedInstruction) },
+		}
+	}
+
+	/// Checks a body against the configured limits and collects its locals, before any of its instructions are checked.
+	/// The body itself is opened as the outermost block, closed by its final `end`.
+	fn prepare_function(&self, state: &mut FunctionState, body: &FuncBody, index: usize) -> Result<Vec<LocalGroup>, InstructionError> {
+		if self.max_instructions_per_function.is_some_and(|limit| body.code().elements().len() > limit) {
+			return Err(InstructionError::LimitExceeded(Limit::Instructions))
+		}
+		let locals = self.get_locals(body, index)?;
+		state.enter_function(results(self.get_function_type(index)?));
+		Ok(locals)
+	}
+
+	/// Collects the local variables of the function at `index`, including its parameters.
+	/// This is done once per body, before any of its instructions are checked.
+	fn get_locals(&self, body: &FuncBody, index: usize) -> Result<Vec<LocalGroup>, InstructionError> {
+		let params = self.get_function_type(index)?.params();
+		// locals are indexed by a u32, so a body can't declare more than that many along with its parameters
+		let declared: u64 = body.locals().iter().map(|local| u64::from(local.count())).sum();
+		let total = declared + params.len() as u64;
+		if total > u64::from(u32::MAX) {
+			return Err(InstructionError::TooManyLocals)
+		}
+		if self.max_locals_per_function.is_some_and(|limit| total > limit as u64) {
+			return Err(InstructionError::LimitExceeded(Limit::Locals))
+		}
+		// parameters take the first indices and aren't part of the body's locals, so they go in front of them
+		let groups = params.iter().map(|param| (1, *param))
+			.chain(body.locals().iter().map(|local| (u64::from(local.count()), local.value_type())));
+		let mut end = 0;
+		Ok(groups.map(|(count, value_type)| {
+			end += count;
+			(end, value_type)
+		}).collect())
+	}
+
+	/// Looks up the type of the global at `index`.
+	/// Imported globals come first in the index space, followed by the ones defined in the module.
+	fn resolve_global(&self, index: u32) -> Option<&'a GlobalType> {
+		let imported = self.module.import_section().into_iter()
+			.flat_map(|section| section.entries())
+			.filter_map(|entry| match entry.external() {
+				External::Global(global_type) => Some(global_type),
+				_ => None,
+			});
+		let defined = self.module.global_section().into_iter()
+			.flat_map(|section| section.entries())
+			.map(|entry| entry.global_type());
+
+		imported.chain(defined).nth(index as usize)
+	}
+
+	/// Looks up the type of the table at `index`, imported tables coming first in the index space
+	fn resolve_table(&self, index: u32) -> Option<&'a TableType> {
+		let imported = self.imports().filter_map(|external| match external {
+			External::Table(table_type) => Some(table_type),
+			_ => None,
+		});
+		let defined = self.module.table_section().into_iter()
+			.flat_map(|section| section.entries());
+
+		imported.chain(defined).nth(index as usize)
+	}
+
+	/// Whether the module defines or imports a linear memory
+	fn has_memory(&self) -> bool {
+		self.memory_count() > 0
+	}
+
+	/// The entities the module imports, in the order they are declared
+	fn imports(&self) -> impl Iterator<Item = &'a External> {
+		self.module.import_section().into_iter()
+			.flat_map(|section| section.entries())
+			.map(|entry| entry.external())
+	}
+
+	/// The number of functions in the function index space, imported ones included
+	fn function_count(&self) -> usize {
+		let imported = self.imports().filter(|external| matches!(external, External::Function(_))).count();
+		imported + self.module.function_section().map_or(0, |section| section.entries().len())
+	}
+
+	/// The number of tables in the table index space, imported ones included
+	fn table_count(&self) -> usize {
+		let imported = self.imports().filter(|external| matches!(external, External::Table(_))).count();
+		imported + self.module.table_section().map_or(0, |section| section.entries().len())
+	}
+
+	/// The number of memories in the memory index space, imported ones included
+	fn memory_count(&self) -> usize {
+		let imported = self.imports().filter(|external| matches!(external, External::Memory(_))).count();
+		imported + self.module.memory_section().map_or(0, |section| section.entries().len())
+	}
+
+	/// The number of globals in the global index space, imported ones included
+	fn global_count(&self) -> usize {
+		let imported = self.imports().filter(|external| matches!(external, External::Global(_))).count();
+		imported + self.module.global_section().map_or(0, |section| section.entries().len())
+	}
+
+	/// Looks up the type of the function at `index` in the function index space.
+	/// Imported functions come first, followed by the ones defined in the module.
+	fn resolve_function_type(&self, index: u32) -> Option<&'a FunctionType> {
+		let imported = self.module.import_section().into_iter()
+			.flat_map(|section| section.entries())
+			.filter_map(|entry| match entry.external() {
+				External::Function(type_ref) => Some(*type_ref),
+				_ => None,
+			});
+		let defined = self.module.function_section().into_iter()
+			.flat_map(|section| section.entries())
+			.map(|function| function.type_ref());
+
+		let type_ref = imported.chain(defined).nth(index as usize)?;
+		match self.module.type_section()?.types().get(type_ref as usize)? {
+			Type::Function(function_type) => Some(function_type),
+		}
+	}
+
+	/// Looks up the `FunctionType` of the function at `index` in the code section
+	/// A body without a matching function or type section means the module itself is malformed.
+	fn get_function_type(&self, index: usize) -> Result<&'a FunctionType, InstructionError> {
+		// type_ref is the index of the FunctionType in types_section
+		let type_ref = self.module.function_section()
+			.and_then(|section| section.entries().get(index))
+			.ok_or(InstructionError::MalformedModule)?
+			.type_ref();
+		let types = self.module.type_section().ok_or(InstructionError::MalformedModule)?.types();
+		match types.get(type_ref as usize) {
+			Some(Type::Function(ftype)) => Ok(ftype),
+			None => Err(InstructionError::TypeNotFound(type_ref)),
+		}
+	}
+}
+
+/// A validator that owns its module, so it can be stored without a lifetime.
+/// `ModuleValidator` borrows the module instead and avoids moving it.
+/// Other settings come from `ModuleValidatorBuilder::build_owned`.
+pub struct OwnedModuleValidator {
+	module: Module,
+	options: Options,
+	source: Option<Vec<u8>>,
+	/// Kept across every `validator()`, so names and offsets are only read once
+	lazy: LazyParts,
+}
+
+impl OwnedModuleValidator {
+
+	/// Takes ownership of the module to validate
+	pub fn new(module: Module, filter: Filter) -> Self {
+		OwnedModuleValidator{
+			module,
+			options: Options { filter, ..Options::default() },
+			source: None,
+			lazy: LazyParts::default(),
+		}
+	}
+
+	/// The module being validated
+	pub fn module(&self) -> &Module {
+		&self.module
+	}
+
+	/// Borrows a `ModuleValidator` for the module, for everything besides `validate`
+	pub fn validator(&self) -> ModuleValidator<'_> {
+		ModuleValidator::with_options(&self.module, &self.options, self.source.as_deref(), Cow::Borrowed(&self.lazy))
+	}
+
+	/// Validates the owned module, see `ModuleValidator::validate`
+	pub fn validate(&self) -> Result<bool, LocatedError> {
+		self.validator().validate()
+	}
+
+	/// Gives the module back
+	pub fn into_module(self) -> Module {
+		self.module
+	}
+}
+
+/// The state of a single function body while it is being checked.
+/// Each body gets its own, so the validator itself is never modified and can be shared.
+struct FunctionState {
+	stack: Vec<ValueType>,
+	/// How many values at the bottom of the stack the current instruction has left alone
+	untouched: usize,
+	/// The values the current instruction removed from below `untouched`, topmost first.
+	/// Together they give the stack as it was before the instruction, which errors report.
+	popped: Vec<ValueType>,
+	/// Set once the rest of the current block can never execute (e.g. after `unreachable`),
+	/// at which point the stack may be treated as holding values of any type.
+	polymorphic: bool,
+	/// The blocks enclosing the current instruction, innermost last
+	control_stack: Vec<ControlFrame>,
+	/// The function being checked, used to locate errors, or `None` for instructions checked outside of any function
+	function_index: Option<usize>,
+	/// The position of the instruction currently being checked, used to locate errors
+	instruction_index: usize,
+}
+
+impl FunctionState {
+
+	/// Starts the state for the function whose body is at `index` in the code section, if the instructions are part of one
+	fn new(index: Option<usize>) -> Self {
+		// every function starts with an empty stack, so nothing can leak in from the previous body
+		FunctionState {
+			stack: vec![],
+			untouched: 0,
+			popped: vec![],
+			polymorphic: false,
+			control_stack: vec![],
+			function_index: index,
+			instruction_index: 0,
+		}
+	}
+
+	/// Records which instruction is being checked, so the stack it started with can be rebuilt for its errors
+	fn begin_instruction(&mut self, position: usize) {
+		self.instruction_index = position;
+		self.untouched = self.stack.len();
+		self.popped.clear();
+	}
+
+	/// The operand stack as it was before the current instruction, only rebuilt once an error needs it
+	fn stack_before(&self) -> Vec<ValueType> {
+		self.stack[..self.untouched].iter().chain(self.popped.iter().rev()).copied().collect()
+	}
+
+	/// The values the current instruction took off the stack so far, bottom first
+	fn popped_operands(&self) -> Vec<ValueType> {
+		self.popped.iter().rev().copied().collect()
+	}
+
+	/// The values the current instruction left on top of the stack so far
+	fn pushed_operands(&self) -> &[ValueType] {
+		&self.stack[self.untouched..]
+	}
+
+	/// Removes the value on top of the stack, remembering it if it was there before the current instruction
+	fn remove_top(&mut self) -> Option<ValueType> {
+		let value = self.stack.pop()?;
+		if self.stack.len() < self.untouched {
+			self.untouched = self.stack.len();
+			self.popped.push(value);
+		}
+		Some(value)
+	}
+
+	/// Removes every value above `height`, see `remove_top`
+	fn truncate_stack(&mut self, height: usize) {
+		while self.stack.len() > height {
+			self.remove_top();
+		}
+	}
+
+	/// The function being checked, for the instructions that depend on its type
+	fn function(&self) -> Result<usize, InstructionError> {
+		self.function_index.ok_or(InstructionError::NotInFunction)
+	}
+
+	/// Pops and pushes what a signature says without checking the types of the popped values
+	fn apply_unchecked(&mut self, signature: &Signature) {
+		for _ in &signature.pop {
+			self.pop_operand();
+		}
+		self.stack.extend(&signature.push);
+	}
+
+	/// Evaluates a signature and determines if the stack can support the instruction in it's current state
+	fn validate_instruction(&mut self, signature: &Signature) -> Result<bool, InstructionError> {
+		for signature_value in &signature.pop {
+			self.pop_expected(*signature_value)?;
+		}
+		self.stack.extend(&signature.push);
+
+		Ok(true)
+	}
+
+	/// Opens the frame of the function body, whose results are the function's
+	fn enter_function(&mut self, result: Vec<ValueType>) {
+		self.control_stack.push(ControlFrame {
+			kind: BlockKind::Function,
+			height: 0,
+			result,
+			enclosing_polymorphic: false,
+		});
+	}
+
+	/// Opens a new control frame starting at the current stack height
+	fn push_frame(&mut self, kind: BlockKind, block_type: BlockType) {
+		let result = block_results(block_type);
+		self.control_stack.push(ControlFrame {
+			kind,
+			height: self.stack.len(),
+			result,
+			enclosing_polymorphic: self.polymorphic,
+		});
+		// a new block is reachable even if the code around it is not
+		self.polymorphic = false;
+	}
+
+	/// Checks that the values pushed since the stack was at `height` are exactly the `expected` types.
+	/// A block has to end with nothing but its results, unlike a branch which discards anything below them.
+	fn check_block_results(&self, expected: &[ValueType], height: usize) -> Result<(), InstructionError> {
+		let found = &self.stack[height.min(self.stack.len())..];
+		if found == expected || (self.polymorphic && expected.ends_with(found)) {
+			Ok(())
+		} else {
+			Err(InstructionError::ResultMismatch { expected: expected.to_vec(), found: found.to_vec() })
+		}
+	}
+
+	/// Checks that the values pushed since the stack was at `height` end with the `expected` types
+	fn check_results(&self, expected: &[ValueType], height: usize) -> Result<(), InstructionError> {
+		let found = &self.stack[height.min(self.stack.len())..];
+		if found.ends_with(expected) || (self.polymorphic && expected.ends_with(found)) {
+			Ok(())
+		} else {
+			Err(InstructionError::ResultMismatch { expected: expected.to_vec(), found: found.to_vec() })
+		}
+	}
+
+	/// Marks the rest of the current block as unreachable, discarding the values it has pushed so far
+	fn set_unreachable(&mut self) {
+		let height = self.current_height();
+		self.truncate_stack(height);
+		self.polymorphic = true;
+	}
+
+	/// Pops the value on top of the stack, unless it belongs to a block enclosing the current one.
+	/// A block can only consume what was pushed inside it, so reaching further down is an underflow.
+	fn pop_operand(&mut self) -> Option<ValueType> {
+		if self.stack.len() > self.current_height() {
+			self.remove_top()
+		} else {
+			None
+		}
+	}
+
+	/// Pops a value that has to be of the `expected` type.
+	/// Unreachable code may pop values that were never pushed, but the ones it did push still have to fit.
+	fn pop_expected(&mut self, expected: ValueType) -> Result<(), InstructionError> {
+		match self.pop_operand() {
+			Some(value) if value == expected => Ok(()),
+			Some(found) => Err(InstructionError::InvalidOperation { expected, found }),
+			None if self.polymorphic => Ok(()),
+			None => Err(InstructionError::StackUnderflow),
+		}
+	}
+
+	/// The stack height the innermost block was entered with, or zero at the top level of the function
+	fn current_height(&self) -> usize {
+		self.control_stack.last().map_or(0, |frame| frame.height)
+	}
+
+	/// Handles the parametric instructions, which operate on values of any type
+	fn validate_parametric(&mut self, instruction: &Instruction) -> Result<bool, InstructionError> {
+		match instruction {
+			Instruction::Drop => {
+				match self.pop_operand() {
+					Some(_) => Ok(true),
+					None if self.polymorphic => Ok(true),
+					None => Err(InstructionError::StackUnderflow),
+				}
+			},
+			Instruction::Select => {
+				// select is untyped in the binary, so the operand type comes from the stack itself
+				self.pop_expected(ValueType::I32)?;
+				match (self.pop_operand(), self.pop_operand()) {
+					(Some(first), Some(second)) => {
+						// the operand on top decides the type, so the one below it is the one that doesn't fit
+						if first != second {
+							return Err(InstructionError::InvalidOperation { expected: first, found: second })
+						}
+						self.stack.push(first);
+						Ok(true)
+					},
+					(first, _) if self.polymorphic => {
+						self.stack.extend(first);
+						Ok(true)
+					},
+					_ => Err(InstructionError::StackUnderflow),
+				}
+			},
+			_ => { Err(InstructionError::UnmatchedInstruction) },
+		}
+	}
+}
+
+/// The values a function leaves on the stack, in order.
+/// Results are compared as whole lists everywhere, so only this has to change once parity-wasm supports multi-value.
+fn results(function_type: &FunctionType) -> Vec<ValueType> {
+	function_type.return_type().into_iter().collect()
+}
+
+/// The values a block leaves on the stack once it ends.
+/// parity-wasm only reads empty and single-value block types, so blocks never take parameters.
+/// Type-indexed blocks will need their params popped on entry here once it can read them.
+fn block_results(block_type: BlockType) -> Vec<ValueType> {
+	match block_type {
+		BlockType::Value(value_type) => vec![value_type],
+		BlockType::NoResult => vec![],
+	}
+}
+
+/// Finds the type of the local at `index`, in the first group that ends past it
+fn local_type(locals: &[LocalGroup], index: u32) -> Option<ValueType> {
+	let group = locals.partition_point(|(end, _)| *end <= u64::from(index));
+	locals.get(group).map(|(_, value_type)| *value_type)
+}
+
+/// The position of the body's closing `end`, where errors about the function's result are reported
+fn last_position(body: &FuncBody) -> usize {
+	body.code().elements().len().saturating_sub(1)
+}
+
+/// The most 64KiB pages a memory can have
+const MAX_PAGES: u32 = 65536;
+
+/// Whether limits with a maximum have it no smaller than their minimum
+fn limits_ordered(limits: &ResizableLimits) -> bool {
+	limits.maximum().is_none_or(|maximum| maximum >= limits.initial())
+}
+
+/// Whether a memory's limits are ordered and stay within the addressable pages
+fn memory_limits_valid(limits: &ResizableLimits) -> bool {
+	let pages_valid = limits.initial() <= MAX_PAGES && limits.maximum().is_none_or(|maximum| maximum <= MAX_PAGES);
+	pages_valid && limits_ordered(limits)
+}
+
+/// Checks whether an instruction belongs to the given classification while ignoring its immediates
+fn is_category(instruction: &Instruction, category: Category) -> bool {
+	classify(instruction) == Some(category)
+}
+
+/// Looks up which family an instruction belongs to, ignoring its immediates.
+/// Instructions outside of every family, like those of the SIMD proposal, give `None`.
+pub fn classify_instruction(instruction: &Instruction) -> Option<Category> {
+	classify(instruction)
+}
+
+/// Reads where each instruction of each function body starts in a module's binary, walking it the way parity-wasm does
+fn instruction_offsets(bytes: &[u8]) -> Option<Vec<Vec<usize>>> {
+	let mut reader = io::Cursor::new(bytes);
+	// the magic number and version come before any section
+	reader.set_position(8);
+	while (reader.position() as usize) < bytes.len() {
+		let id = u8::from(Uint8::deserialize(&mut reader).ok()?);
+		let size = u32::from(VarUint32::deserialize(&mut reader).ok()?) as u64;
+		// the code section's id
+		if id != 10 {
+			reader.set_position(reader.position() + size);
+			continue
+		}
+		let count = u32::from(VarUint32::deserialize(&mut reader).ok()?);
+		let mut offsets = vec![];
+		for _ in 0..count {
+			let size = u32::from(VarUint32::deserialize(&mut reader).ok()?) as u64;
+			let end = reader.position() + size;
+			CountedList::<Local>::deserialize(&mut reader).ok()?;
+			let mut body = vec![];
+			while reader.position() < end {
+				body.push(reader.position() as usize);
+				Instruction::deserialize(&mut reader).ok()?;
+			}
+			offsets.push(body);
+		}
+		return Some(offsets)
+	}
+	None
+}
+
+/// Whether the module is valid, treating any error as invalid
+pub fn is_valid(module: &Module, filter: Filter) -> bool {
+	ModuleValidator::new(module, filter).validate().unwrap_or(false)
+}
+
+/// Deserializes a module from raw bytes and validates it in one step
+pub fn validate_bytes(bytes: &[u8], filter: Filter) -> Result<bool, ValidationError> {
+	let module = deserialize_buffer::<Module>(bytes)?;
+	Ok(ModuleValidatorBuilder::new(&module).filter(filter).source(bytes).build().validate()?)
+}
+
+/// Validates bytes from an untrusted source with every check enabled.
+/// Sections, names and other lengths the input declares are read no further than the bytes actually there,
+/// so truncated or oversized declarations come back as errors instead of reserving memory for them.
+pub fn validate_untrusted(bytes: &[u8]) -> Result<bool, ValidationError> {
+	validate_bytes(bytes, Filter::all())
+}
+
+/// Assembles a module from the WebAssembly text format and validates it in one step
+#[cfg(feature = "wat")]
+pub fn validate_wat(source: &str, filter: Filter) -> Result<bool, ValidationError> {
+	let bytes = wat::parse_str(source)?;
+	validate_bytes(&bytes, filter)
+}
+
+/// Reads a module from a file and validates it in one step
+pub fn validate_file<P: AsRef<Path>>(path: P, filter: Filter) -> Result<bool, ValidationError> {
+	let bytes = std::fs::read(path)?;
+	validate_bytes(&bytes, filter)
+}
+
+/// Validates each module on its own, returning one result per module in the same order
+pub fn validate_modules(modules: &[Module], filter: Filter) -> Vec<Result<bool, LocatedError>> {
+	modules.iter()
+		.map(|module| ModuleValidator::new(module, filter).validate())
+		.collect()
+}
+
+/// The name a value type has in the text format, e.g. `"i32"`
+pub fn value_type_name(value_type: ValueType) -> &'static str {
+	match value_type {
+		ValueType::I32 => "i32",
+		ValueType::I64 => "i64",
+		ValueType::F32 => "f32",
+		ValueType::F64 => "f64",
+		#[cfg(feature = "simd")]
+		ValueType::V128 => "v128",
+	}
+}
+
+/// The value type with the given text format name, the inverse of `value_type_name`
+pub fn value_type_from_name(name: &str) -> Option<ValueType> {
+	match name {
+		"i32" => Some(ValueType::I32),
+		"i64" => Some(ValueType::I64),
+		"f32" => Some(ValueType::F32),
+		"f64" => Some(ValueType::F64),
+		#[cfg(feature = "simd")]
+		"v128" => Some(ValueType::V128),
+		_ => None,
+	}
+}
+
+/// Reads the module's `name` section, whether or not it has been parsed with `Module::parse_names` yet
+fn read_names(module: &Module) -> Option<NameSection> {
+	if let Some(names) = module.names_section() {
+		return Some(names.clone())
+	}
+	module.sections().iter()
+		.filter_map(|section| match section {
+			Section::Custom(custom) if custom.name() == "name" => Some(custom.payload()),
+			_ => None,
+		})
+		.find(|payload| name_counts_fit(payload).is_some())
+		.and_then(|mut payload| NameSection::deserialize(module, &mut payload).ok())
+}
+
+/// Checks that every count in a `name` section fits in the bytes left after it.
+/// parity-wasm reserves room for as many names as a subsection claims before reading any of them,
+/// so a few bytes claiming billions of names would otherwise exhaust memory.
+fn name_counts_fit(payload: &[u8]) -> Option<()> {
+	let mut reader = io::Cursor::new(payload);
+	// a count can't exceed the bytes left, since every entry takes at least one
+	let read_count = |reader: &mut io::Cursor<&[u8]>| {
+		let count = u64::from(u32::from(VarUint32::deserialize(reader).ok()?));
+		Some(count).filter(|count| *count <= payload.len() as u64 - reader.position())
+	};
+	while (reader.position() as usize) < payload.len() {
+		let subsection = u8::from(VarUint7::deserialize(&mut reader).ok()?);
+		let size = read_count(&mut reader)?;
+		let end = reader.position() + size;
+		// the function names are a map, the local names a map of maps per function
+		let maps = match subsection {
+			1 => 1,
+			2 => read_count(&mut reader)?,
+			_ => 0,
+		};
+		for _ in 0..maps {
+			if subsection == 2 {
+				VarUint32::deserialize(&mut reader).ok()?;
+			}
+			for _ in 0..read_count(&mut reader)? {
+				VarUint32::deserialize(&mut reader).ok()?;
+				let len = read_count(&mut reader)?;
+				reader.set_position(reader.position() + len);
+			}
+		}
+		reader.set_position(end);
+	}
+	Some(())
+}
+
+/// Whether an instruction is handled by `validate_control`
+fn is_control(instruction: &Instruction) -> bool {
+	is_category(instruction, Category::Control)
+}
+
+/// The family an instruction belongs to for the purposes of `Filter`.
+/// Instructions that every family relies on belong to none, so they are never skipped.
+fn instruction_family(instruction: &Instruction) -> Filter {
+	match classify(instruction) {
+		Some(Category::Get) | Some(Category::Set) | Some(Category::Parametric) => Filter::empty(),
+		Some(Category::Control) | Some(Category::Call) => Filter::CONTROL,
+		Some(Category::Load) | Some(Category::Store) | Some(Category::Memory) => Filter::MEMORY,
+		Some(Category::Conversion) => Filter::CONVERSION,
+		Some(_) => Filter::NUMERIC,
+		None => Filter::empty(),
+	}
+}
+
+/// Whether the validator checks an instruction somewhere other than its signature.
+/// `nop` has no effect on the stack, so there is nothing to check.
+fn is_modeled(instruction: &Instruction) -> bool {
+	classify(instruction).is_some()
+}
+
+/// The proposal an instruction comes from, or `None` for MVP instructions
+fn instruction_feature(instruction: &Instruction) -> Option<Feature> {
+	match instruction {
+		#[cfg(feature = "sign_ext")]
+		Instruction::SignExt(_) => Some(Feature::SignExt),
+		#[cfg(feature = "simd")]
+		Instruction::Simd(_) => Some(Feature::Simd),
+		_ => None,
+	}
+}
+
+/// Whether an instruction accesses linear memory, and so requires the module to declare one
+fn uses_memory(instruction: &Instruction) -> bool {
+	is_category(instruction, Category::Load) || is_category(instruction, Category::Store) || is_category(instruction, Category::Memory)
+}
+
+/// Given an instruction, determine it's signature based on what classification it is in.
+fn get_instruction_signature(instruction: &Instruction) -> Option<Signature> {
+	// returns some signature if there is a type we are interested in
+	// returns None otherwise
+	match classify(instruction) {
+		Some(Category::I32Unop) => Some(Signature{ pop: [ValueType::I32; 1].to_vec(), push: [ValueType::I32; 1].to_vec() }),
+		Some(Category::I64Unop) => Some(Signature{ pop: [ValueType::I64; 1].to_vec(), push: [ValueType::I64; 1].to_vec() }),
+		Some(Category::I32Binop) => Some(Signature{ pop: [ValueType::I32; 2].to_vec(), push: [ValueType::I32; 1].to_vec() }),
+		Some(Category::I32Testop) => Some(Signature{ pop: [ValueType::I32; 1].to_vec(), push: [ValueType::I32; 1].to_vec() }),
+		Some(Category::I64Testop) => Some(Signature{ pop: [ValueType::I64; 1].to_vec(), push: [ValueType::I32; 1].to_vec() }),
+		Some(Category::I32Relop) => Some(Signature{ pop: [ValueType::I32; 2].to_vec(), push: [ValueType::I32; 1].to_vec() }),
+		Some(Category::I64Binop) => Some(Signature{ pop: [ValueType::I64; 2].to_vec(), push: [ValueType::I64; 1].to_vec() }),
+		Some(Category::I64Relop) => Some(Signature{ pop: [ValueType::I64; 2].to_vec(), push: [ValueType::I32; 1].to_vec() }),
+		Some(Category::F32Unop) => Some(Signature{ pop: [ValueType::F32; 1].to_vec(), push: [ValueType::F32; 1].to_vec() }),
+		Some(Category::F64Unop) => Some(Signature{ pop: [ValueType::F64; 1].to_vec(), push: [ValueType::F64; 1].to_vec() }),
+		Some(Category::F32Binop) => Some(Signature{ pop: [ValueType::F32; 2].to_vec(), push: [ValueType::F32; 1].to_vec() }),
+		Some(Category::F64Binop) => Some(Signature{ pop: [ValueType::F64; 2].to_vec(), push: [ValueType::F64; 1].to_vec() }),
+		Some(Category::F32Relop) => Some(Signature{ pop: [ValueType::F32; 2].to_vec(), push: [ValueType::I32; 1].to_vec() }),
+		Some(Category::F64Relop) => Some(Signature{ pop: [ValueType::F64; 2].to_vec(), push: [ValueType::I32; 1].to_vec() }),
+		Some(Category::Const) => get_const_signature(instruction),
+		Some(Category::Load) => get_load_signature(instruction),
+		Some(Category::Store) => get_store_signature(instruction),
+		Some(Category::Memory) => get_memory_signature(instruction),
+		#[cfg(feature = "sign_ext")]
+		Some(Category::ExtendSign) => get_sign_ext_signature(instruction),
+		Some(Category::Conversion) => get_conversion_signature(instruction),
+		_ => None,
+	}
+}
+
+/// The declared and natural alignment of a load or store, both as powers of two.
+/// An access may claim to be less aligned than its size, but never more.
+fn get_alignment(instruction: &Instruction) -> Option<(u32, u32)> {
+	match instruction {
+		Instruction::I32Load8S(align, _) | Instruction::I32Load8U(align, _) |
+		Instruction::I64Load8S(align, _) | Instruction::I64Load8U(align, _) |
+		Instruction::I32Store8(align, _) | Instruction::I64Store8(align, _) => Some((*align, 0)),
+		Instruction::I32Load16S(align, _) | Instruction::I32Load16U(align, _) |
+		Instruction::I64Load16S(align, _) | Instruction::I64Load16U(align, _) |
+		Instruction::I32Store16(align, _) | Instruction::I64Store16(align, _) => Some((*align, 1)),
+		Instruction::I32Load(align, _) | Instruction::F32Load(align, _) |
+		Instruction::I64Load32S(align, _) | Instruction::I64Load32U(align, _) |
+		Instruction::I32Store(align, _) | Instruction::F32Store(align, _) | Instruction::I64Store32(align, _) => Some((*align, 2)),
+		Instruction::I64Load(align, _) | Instruction::F64Load(align, _) |
+		Instruction::I64Store(align, _) | Instruction::F64Store(align, _) => Some((*align, 3)),
+		_ => None
+	}
+}
+
+/// Determines the signature of a load instruction, which pops an i32 address and pushes the loaded value
+fn get_load_signature(instruction: &Instruction) -> Option<Signature> {
+	let value = match instruction {
+		Instruction::I32Load(_, _) | Instruction::I32Load8S(_, _) | Instruction::I32Load8U(_, _) |
+		Instruction::I32Load16S(_, _) | Instruction::I32Load16U(_, _) => ValueType::I32,
+		Instruction::I64Load(_, _) | Instruction::I64Load8S(_, _) | Instruction::I64Load8U(_, _) |
+		Instruction::I64Load16S(_, _) | Instruction::I64Load16U(_, _) |
+		Instruction::I64Load32S(_, _) | Instruction::I64Load32U(_, _) => ValueType::I64,
+		Instruction::F32Load(_, _) => ValueType::F32,
+		Instruction::F64Load(_, _) => ValueType::F64,
+		_ => return None
+	};
+
+	Some(Signature{ pop: [ValueType::I32; 1].to_vec(), push: [value; 1].to_vec() })
+}
+
+/// Determines the signature of a store instruction, which pops the value to store and then its i32 address.
+/// The address is pushed first, so the value is on top of the stack and gets popped first.
+fn get_store_signature(instruction: &Instruction) -> Option<Signature> {
+	let value = match instruction {
+		Instruction::I32Store(_, _) | Instruction::I32Store8(_, _) | Instruction::I32Store16(_, _) => ValueType::I32,
+		Instruction::I64Store(_, _) | Instruction::I64Store8(_, _) | Instruction::I64Store16(_, _) |
+		Instruction::I64Store32(_, _) => ValueType::I64,
+		Instruction::F32Store(_, _) => ValueType::F32,
+		Instruction::F64Store(_, _) => ValueType::F64,
+		_ => return None
+	};
+
+	Some(Signature{ pop: [value, ValueType::I32].to_vec(), push: [].to_vec() })
+}
+
+/// Determines the signature of a sign-extension instruction, which pops and pushes the same integer type.
+/// `i64.extend32_s` only looks at the low 32 bits but still works on an i64.
+#[cfg(feature = "sign_ext")]
+fn get_sign_ext_signature(instruction: &Instruction) -> Option<Signature> {
+	let value = match instruction {
+		Instruction::SignExt(SignExtInstruction::I32Extend8S) |
+		Instruction::SignExt(SignExtInstruction::I32Extend16S) => ValueType::I32,
+		Instruction::SignExt(SignExtInstruction::I64Extend8S) |
+		Instruction::SignExt(SignExtInstruction::I64Extend16S) |
+		Instruction::SignExt(SignExtInstruction::I64Extend32S) => ValueType::I64,
+		_ => return None
+	};
+
+	Some(Signature{ pop: [value; 1].to_vec(), push: [value; 1].to_vec() })
+}
+
+/// Determines the signature of `current_memory` and `grow_memory`, which both deal in i32 page counts
+fn get_memory_signature(instruction: &Instruction) -> Option<Signature> {
+	match instruction {
+		Instruction::CurrentMemory(_) => Some(Signature{ pop: [].to_vec(), push: [ValueType::I32; 1].to_vec() }),
+		Instruction::GrowMemory(_) => Some(Signature{ pop: [ValueType::I32; 1].to_vec(), push: [ValueType::I32; 1].to_vec() }),
+		_ => None
+	}
+}
+
+/// Determines the signature of a conversion instruction, which pops a value of one type and pushes another.
+/// Reinterpretations keep the bit width but still change the type, so they are handled the same way.
+fn get_conversion_signature(instruction: &Instruction) -> Option<Signature> {
+	let (from, to) = match instruction {
+		Instruction::I32WrapI64 => (ValueType::I64, ValueType::I32),
+		Instruction::I32TruncSF32 | Instruction::I32TruncUF32 => (ValueType::F32, ValueType::I32),
+		Instruction::I32TruncSF64 | Instruction::I32TruncUF64 => (ValueType::F64, ValueType::I32),
+		Instruction::I64ExtendSI32 | Instruction::I64ExtendUI32 => (ValueType::I32, ValueType::I64),
+		Instruction::I64TruncSF32 | Instruction::I64TruncUF32 => (ValueType::F32, ValueType::I64),
+		Instruction::I64TruncSF64 | Instruction::I64TruncUF64 => (ValueType::F64, ValueType::I64),
+		Instruction::F32ConvertSI32 | Instruction::F32ConvertUI32 => (ValueType::I32, ValueType::F32),
+		Instruction::F32ConvertSI64 | Instruction::F32ConvertUI64 => (ValueType::I64, ValueType::F32),
+		Instruction::F32DemoteF64 => (ValueType::F64, ValueType::F32),
+		Instruction::F64ConvertSI32 | Instruction::F64ConvertUI32 => (ValueType::I32, ValueType::F64),
+		Instruction::F64ConvertSI64 | Instruction::F64ConvertUI64 => (ValueType::I64, ValueType::F64),
+		Instruction::F64PromoteF32 => (ValueType::F32, ValueType::F64),
+		Instruction::I32ReinterpretF32 => (ValueType::F32, ValueType::I32),
+		Instruction::I64ReinterpretF64 => (ValueType::F64, ValueType::I64),
+		Instruction::F32ReinterpretI32 => (ValueType::I32, ValueType::F32),
+		Instruction::F64ReinterpretI64 => (ValueType::I64, ValueType::F64),
+		_ => return None
+	};
+
+	Some(Signature{ pop: [from; 1].to_vec(), push: [to; 1].to_vec() })
+}
+
+/// Determines the signature of a const instruction, which pops nothing and pushes a value of its own type
+fn get_const_signature(instruction: &Instruction) -> Option<Signature> {
+	let value = match instruction {
+		Instruction::I32Const(_) => ValueType::I32,
+		Instruction::I64Const(_) => ValueType::I64,
+		Instruction::F32Const(_) => ValueType::F32,
+		Instruction::F64Const(_) => ValueType::F64,
+		_ => return None
+	};
+
+	Some(Signature{ pop: [].to_vec(), push: [value; 1].to_vec() })
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use parity_wasm::elements::deserialize_buffer;
+	use parity_wasm::deserialize_file;
+
+	// Shared by the `complex_instruction_*` tests
+	// WAST:
+	// (module
+	//   (type $t0 (func (param i32 i32) (result i32)))
+	//   (func $_Z4multii (export "_Z4multii") (type $t0) (param $p0 i32) (param $p1 i32) (result i32)
+	//     (i32.mul
+	//       (get_local $p1)
+	//       (get_local $p0)))
+	//   (func $_Z3addii (export "_Z3addii") (type $t0) (param $p0 i32) (param $p1 i32) (result i32)
+	//     (i32.add
+	//       (get_local $p1)
+	//       (get_local $p0)))
+	//   (func $_Z6divideii (export "_Z6divideii") (type $t0) (param $p0 i32) (param $p1 i32) (result i32)
+	//     (i32.div_s
+	//       (get_local $p0)
+	//       (get_local $p1)))
+	//   (table $T0 0 anyfunc)
+	//   (memory $memory (export "memory") 1))
+	const COMPLEX_INSTRUCTION_WASM: &[u8] = &[
+		0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7f, 0x01,
+		0x7f, 0x03, 0x04, 0x03, 0x00, 0x00, 0x00, 0x04, 0x04, 0x01, 0x70, 0x00, 0x00, 0x05, 0x03, 0x01,
+		0x00, 0x01, 0x07, 0x2f, 0x04, 0x09, 0x5f, 0x5a, 0x34, 0x6d, 0x75, 0x6c, 0x74, 0x69, 0x69, 0x00,
+		0x00, 0x08, 0x5f, 0x5a, 0x33, 0x61, 0x64, 0x64, 0x69, 0x69, 0x00, 0x01, 0x0b, 0x5f, 0x5a, 0x36,
+		0x64, 0x69, 0x76, 0x69, 0x64, 0x65, 0x69, 0x69, 0x00, 0x02, 0x06, 0x6d, 0x65, 0x6d, 0x6f, 0x72,
+		0x79, 0x02, 0x00, 0x0a, 0x19, 0x03, 0x07, 0x00, 0x20, 0x01, 0x20, 0x00, 0x6c, 0x0b, 0x07, 0x00,
+		0x20, 0x01, 0x20, 0x00, 0x6a, 0x0b, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6d, 0x0b, 0x00, 0x4b,
+		0x04, 0x6e, 0x61, 0x6d, 0x65, 0x01, 0x23, 0x03, 0x00, 0x09, 0x5f, 0x5a, 0x34, 0x6d, 0x75, 0x6c,
+		0x74, 0x69, 0x69, 0x01, 0x08, 0x5f, 0x5a, 0x33, 0x61, 0x64, 0x64, 0x69, 0x69, 0x02, 0x0b, 0x5f,
+		0x5a, 0x36, 0x64, 0x69, 0x76, 0x69, 0x64, 0x65, 0x69, 0x69, 0x02, 0x1f, 0x03, 0x00, 0x02, 0x00,
+		0x02, 0x70, 0x30, 0x01, 0x02, 0x70, 0x31, 0x01, 0x02, 0x00, 0x02, 0x70, 0x30, 0x01, 0x02, 0x70,
+		0x31, 0x02, 0x02, 0x00, 0x02, 0x70, 0x30, 0x01, 0x02, 0x70, 0x31
+	];
+
+	#[test]
+	fn simple_instructions_binary() {
+		// WAST:
+		// (module
+		//   (type $t0 (func (param i32 i32) (result i32)))
+		//   (func $f0 (type $t0) (param $p0 i32) (param $p1 i32) (result i32)
+		//     (i32.add
+		//       (get_local $p0)
+		//       (get_local $p1))))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7f, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b,
+			0x00, 0x14, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x02, 0x0d, 0x01, 0x00, 0x02, 0x00, 0x03, 0x6c, 0x68,
+			0x73, 0x01, 0x03, 0x72, 0x68, 0x73
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn const_instructions_binary() {
+		// WAST:
+		// (module
+		//   (table 0 anyfunc)
+		//   (memory $0 1)
+		//   (export "memory" (memory $0))
+		//   (export "_Z9i32_constv" (func $_Z9i32_constv))
+		//   (export "_Z9i64_constv" (func $_Z9i64_constv))
+		//   (export "_Z9f32_constv" (func $_Z9f32_constv))
+		//   (export "_Z9f64_constv" (func $_Z9f64_constv))
+		//   (func $_Z9i32_constv (; 0 ;) (result i32)
+		//     (i32.const 2147483647)
+		//   )
+		//   (func $_Z9i64_constv (; 1 ;) (result i64)
+		//     (i64.const 9223372036854775807)
+		//   )
+		//   (func $_Z9f32_constv (; 2 ;) (result f32)
+		//     (f32.const 3402823466385288598117041e14)
+		//   )
+		//   (func $_Z9f64_constv (; 3 ;) (result f64)
+		//     (f64.const 1.8e+307)
+		//   )
+		// )
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x91, 0x80, 0x80, 0x80, 0x00, 0x04, 0x60,
+			0x00, 0x01, 0x7f, 0x60, 0x00, 0x01, 0x7e, 0x60, 0x00, 0x01, 0x7d, 0x60, 0x00, 0x01, 0x7c, 0x03,
+			0x85, 0x80, 0x80, 0x80, 0x00, 0x04, 0x00, 0x01, 0x02, 0x03, 0x04, 0x84, 0x80, 0x80, 0x80, 0x00,
+			0x01, 0x70, 0x00, 0x00, 0x05, 0x83, 0x80, 0x80, 0x80, 0x00, 0x01, 0x00, 0x01, 0x06, 0x81, 0x80,
+			0x80, 0x80, 0x00, 0x00, 0x07, 0xca, 0x80, 0x80, 0x80, 0x00, 0x05, 0x06, 0x6d, 0x65, 0x6d, 0x6f,
+			0x72, 0x79, 0x02, 0x00, 0x0d, 0x5f, 0x5a, 0x39, 0x69, 0x33, 0x32, 0x5f, 0x63, 0x6f, 0x6e, 0x73,
+			0x74, 0x76, 0x00, 0x00, 0x0d, 0x5f, 0x5a, 0x39, 0x69, 0x36, 0x34, 0x5f, 0x63, 0x6f, 0x6e, 0x73,
+			0x74, 0x76, 0x00, 0x01, 0x0d, 0x5f, 0x5a, 0x39, 0x66, 0x33, 0x32, 0x5f, 0x63, 0x6f, 0x6e, 0x73,
+			0x74, 0x76, 0x00, 0x02, 0x0d, 0x5f, 0x5a, 0x39, 0x66, 0x36, 0x34, 0x5f, 0x63, 0x6f, 0x6e, 0x73,
+			0x74, 0x76, 0x00, 0x03, 0x0a, 0xbc, 0x80, 0x80, 0x80, 0x00, 0x04, 0x88, 0x80, 0x80, 0x80, 0x00,
+			0x00, 0x41, 0xff, 0xff, 0xff, 0xff, 0x07, 0x0b, 0x8d, 0x80, 0x80, 0x80, 0x00, 0x00, 0x42, 0xff,
+			0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x0b, 0x87, 0x80, 0x80, 0x80, 0x00, 0x00,
+			0x43, 0xff, 0xff, 0x7f, 0x7f, 0x0b, 0x8b, 0x80, 0x80, 0x80, 0x00, 0x00, 0x44, 0x2e, 0x02, 0x68,
+			0x83, 0x02, 0xa2, 0xb9, 0x7f, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	#[should_panic]
+	fn unmatched_type_failure_binary() {
+		// Binary incorrectly tries to add an f64 with i32 to return an i32
+		// This should be failed by the validator
+		// WAST:
+		// (module
+		//   (func $addTwo (param f64 i32) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     i32.add)
+		//   (export "addTwo" (func $addTwo)))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7c, 0x7f, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x07, 0x0a, 0x01, 0x06, 0x61, 0x64, 0x64, 0x54, 0x77, 0x6f, 0x00,
+			0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b, 0x00, 0x19, 0x04, 0x6e,
+			0x61, 0x6d, 0x65, 0x01, 0x09, 0x01, 0x00, 0x06, 0x61, 0x64, 0x64, 0x54, 0x77, 0x6f, 0x02, 0x07,
+			0x01, 0x00, 0x02, 0x00, 0x00, 0x01, 0x00
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn simple_wasm_from_file() {
+		// WAST:
+		// (module
+		//   (func $addTwo (param f64 i32) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     i32.add)
+		//   (export "addTwo" (func $addTwo)))
+		let module = deserialize_file("./src/wasm_binaries/test.wasm").unwrap();
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn complex_instruction_binary() {
+		let module = deserialize_buffer::<Module>(COMPLEX_INSTRUCTION_WASM).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	#[should_panic]
+	fn stack_reset_between_functions() {
+		// The first function leaves an i32 behind, which must not be visible to the second function.
+		// The second function's i32.add only has one operand, so it has to fail on its own.
+		// WAST:
+		// (module
+		//   (func $f0 (result i32)
+		//     i32.const 1)
+		//   (func $f1 (result i32)
+		//     i32.const 2
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x03, 0x02, 0x00, 0x00, 0x0a, 0x0c, 0x02, 0x04, 0x00, 0x41, 0x01, 0x0b, 0x05, 0x00, 0x41, 0x02,
+			0x6a, 0x0b, 0x00, 0x10, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x01, 0x09, 0x02, 0x00, 0x02, 0x66, 0x30,
+			0x01, 0x02, 0x66, 0x31
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn result_matches_declared_type() {
+		// WAST:
+		// (module
+		//   (func (param i64 i64) (result i64)
+		//     get_local 0
+		//     get_local 1
+		//     i64.mul))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7e, 0x7e, 0x01,
+			0x7e, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x7e, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn result_wrong_type_failure() {
+		// Function declares an i32 result but leaves an f64 on the stack
+		// WAST:
+		// (module
+		//   (func (param f64) (result i32)
+		//     get_local 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7c, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x06, 0x01, 0x04, 0x00, 0x20, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::ResultMismatch { expected, found }, .. }) => {
+				assert_eq!(expected, vec![ValueType::I32]);
+				assert_eq!(found, vec![ValueType::F64]);
+			}
+			_ => panic!("expected a result mismatch"),
+		}
+	}
+
+	#[test]
+	fn result_extra_values_failure() {
+		// Function declares a single i32 result but leaves two on the stack
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1
+		//     i32.const 2))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x08, 0x01, 0x06, 0x00, 0x41, 0x01, 0x41, 0x02, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::ResultMismatch { expected, found }, .. }) => {
+				assert_eq!(expected, vec![ValueType::I32]);
+				assert_eq!(found, vec![ValueType::I32, ValueType::I32]);
+			}
+			_ => panic!("expected a result mismatch"),
+		}
+	}
+
+	#[test]
+	fn i32_comparison_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32 i32) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     i32.lt_s))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7f, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x48, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	#[should_panic]
+	fn i32_comparison_failure_binary() {
+		// Comparing an i32 against an i64 should be rejected
+		// WAST:
+		// (module
+		//   (func (param i32 i64) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     i32.ge_u))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7e, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x4f, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn i64_comparison_binary() {
+		// i64 comparisons produce an i32, not an i64
+		// WAST:
+		// (module
+		//   (func (param i64 i64) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     i64.ge_u))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7e, 0x7e, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x5a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn float_comparison_binary() {
+		// Float comparisons push an i32, so their results can be added as integers
+		// WAST:
+		// (module
+		//   (func (param f32 f32 f64 f64) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     f32.lt
+		//     get_local 2
+		//     get_local 3
+		//     f64.ge
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x09, 0x01, 0x60, 0x04, 0x7d, 0x7d, 0x7c,
+			0x7c, 0x01, 0x7f, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x0f, 0x01, 0x0d, 0x00, 0x20, 0x00, 0x20, 0x01,
+			0x5d, 0x20, 0x02, 0x20, 0x03, 0x66, 0x6a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	#[should_panic]
+	fn float_comparison_failure_binary() {
+		// The comparison results are i32, so they cannot be fed to f32.add
+		// WAST:
+		// (module
+		//   (func (param f32 f32) (result f32)
+		//     get_local 0
+		//     get_local 1
+		//     f32.eq
+		//     get_local 0
+		//     get_local 1
+		//     f32.ne
+		//     f32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7d, 0x7d, 0x01,
+			0x7d, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x0f, 0x01, 0x0d, 0x00, 0x20, 0x00, 0x20, 0x01, 0x5b, 0x20,
+			0x00, 0x20, 0x01, 0x5c, 0x92, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn eqz_instruction_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     i32.eqz))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x07, 0x01, 0x05, 0x00, 0x20, 0x00, 0x45, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	#[should_panic]
+	fn eqz_wrong_type_failure_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     i64.eqz))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x07, 0x01, 0x05, 0x00, 0x20, 0x00, 0x50, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn integer_unary_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     i32.ctz
+		//     i32.popcnt))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x08, 0x01, 0x06, 0x00, 0x20, 0x00, 0x68, 0x69, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn integer_unary_wrong_type_failure_binary() {
+		// i32.clz cannot count the leading zeros of an f32
+		// WAST:
+		// (module
+		//   (func (param f32) (result i32)
+		//     get_local 0
+		//     i32.clz))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7d, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x07, 0x01, 0x05, 0x00, 0x20, 0x00, 0x67, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		match validator.validate() {
+			Err(LocatedError { function_index: Some(0), instruction_index: Some(1), error: InstructionError::InvalidOperation { .. }, .. }) => (),
+			_ => panic!("expected i32.clz to reject an f32 operand"),
+		}
+	}
+
+	#[test]
+	fn float_unary_binary() {
+		// WAST:
+		// (module
+		//   (func (param f64) (result f64)
+		//     get_local 0
+		//     f64.sqrt))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7c, 0x01, 0x7c,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x07, 0x01, 0x05, 0x00, 0x20, 0x00, 0x9f, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	#[should_panic]
+	fn float_unary_wrong_type_failure_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result f64)
+		//     get_local 0
+		//     f64.sqrt))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7c,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x07, 0x01, 0x05, 0x00, 0x20, 0x00, 0x9f, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn wrap_conversion_binary() {
+		// WAST:
+		// (module
+		//   (func (param i64) (result i32)
+		//     get_local 0
+		//     i32.wrap/i64))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7e, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x07, 0x01, 0x05, 0x00, 0x20, 0x00, 0xa7, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn convert_conversion_binary() {
+		// WAST:
+		// (module
+		//   (func (param i64) (result f64)
+		//     get_local 0
+		//     f64.convert_s/i64))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7e, 0x01, 0x7c,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x07, 0x01, 0x05, 0x00, 0x20, 0x00, 0xb9, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	#[should_panic]
+	fn reinterpret_conversion_failure_binary() {
+		// i32.reinterpret/f32 expects an f32 operand even though it produces an i32
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     i32.reinterpret/f32))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x07, 0x01, 0x05, 0x00, 0x20, 0x00, 0xbc, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONVERSION);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn drop_instruction_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32 f64) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     drop))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7c, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x1a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn drop_empty_stack_failure_binary() {
+		// WAST:
+		// (module
+		//   (func
+		//     drop))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02,
+			0x01, 0x00, 0x0a, 0x05, 0x01, 0x03, 0x00, 0x1a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::StackUnderflow, .. }) => (),
+			_ => panic!("expected drop to underflow the stack"),
+		}
+	}
+
+	#[test]
+	fn select_instruction_binary() {
+		// WAST:
+		// (module
+		//   (func (param f32 f32 i32) (result f32)
+		//     get_local 0
+		//     get_local 1
+		//     get_local 2
+		//     select))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x01, 0x60, 0x03, 0x7d, 0x7d, 0x7f,
+			0x01, 0x7d, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x0b, 0x01, 0x09, 0x00, 0x20, 0x00, 0x20, 0x01, 0x20,
+			0x02, 0x1b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn select_mismatched_operands_failure_binary() {
+		// Both select operands must have the same type
+		// WAST:
+		// (module
+		//   (func (param f32 i64 i32) (result f32)
+		//     get_local 0
+		//     get_local 1
+		//     get_local 2
+		//     select))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x01, 0x60, 0x03, 0x7d, 0x7e, 0x7f,
+			0x01, 0x7d, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x0b, 0x01, 0x09, 0x00, 0x20, 0x00, 0x20, 0x01, 0x20,
+			0x02, 0x1b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		match validator.validate() {
+			Err(LocatedError { function_index: Some(0), instruction_index: Some(3), error: InstructionError::InvalidOperation { .. }, .. }) => (),
+			_ => panic!("expected select to reject operands of different types"),
+		}
+	}
+
+	#[test]
+	fn unreachable_polymorphic_stack_binary() {
+		// Anything after unreachable can never run, so i32.add on an empty stack is allowed
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     unreachable
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x06, 0x01, 0x04, 0x00, 0x00, 0x6a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	#[should_panic]
+	fn unreachable_reset_between_functions_binary() {
+		// The second function is reachable again, so its i32.add must still have operands
+		// WAST:
+		// (module
+		//   (func
+		//     unreachable)
+		//   (func (result i32)
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x02, 0x60, 0x00, 0x00, 0x60, 0x00,
+			0x01, 0x7f, 0x03, 0x03, 0x02, 0x00, 0x01, 0x0a, 0x09, 0x02, 0x03, 0x00, 0x00, 0x0b, 0x03, 0x00,
+			0x6a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn set_local_instruction_binary() {
+		// WAST:
+		// (module
+		//   (func (result f64)
+		//     (local f64)
+		//     f64.const 1.5
+		//     set_local 0
+		//     get_local 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7c, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x13, 0x01, 0x11, 0x01, 0x01, 0x7c, 0x44, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0x00, 0xf8, 0x3f, 0x21, 0x00, 0x20, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn set_local_wrong_type_failure_binary() {
+		// Storing an i32 into an f64 local should be rejected
+		// WAST:
+		// (module
+		//   (func
+		//     (local f64)
+		//     i32.const 1
+		//     set_local 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02,
+			0x01, 0x00, 0x0a, 0x0a, 0x01, 0x08, 0x01, 0x01, 0x7c, 0x41, 0x01, 0x21, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		match validator.validate() {
+			Err(LocatedError { function_index: Some(0), instruction_index: Some(1), error: InstructionError::InvalidOperation { .. }, .. }) => (),
+			_ => panic!("expected set_local to reject an i32 for an f64 local"),
+		}
+	}
+
+	#[test]
+	fn set_local_missing_local_failure_binary() {
+		// WAST:
+		// (module
+		//   (func
+		//     i32.const 1
+		//     set_local 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02,
+			0x01, 0x00, 0x0a, 0x08, 0x01, 0x06, 0x00, 0x41, 0x01, 0x21, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::LocalNotFound, .. }) => (),
+			_ => panic!("expected set_local to fail on a missing local"),
+		}
+	}
+
+	#[test]
+	fn tee_local_instruction_binary() {
+		// The value stored by tee_local stays on the stack and feeds i64.add
+		// WAST:
+		// (module
+		//   (func (param i64) (result i64)
+		//     (local i64)
+		//     get_local 0
+		//     tee_local 1
+		//     get_local 1
+		//     i64.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7e, 0x01, 0x7e,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x0d, 0x01, 0x0b, 0x01, 0x01, 0x7e, 0x20, 0x00, 0x22, 0x01, 0x20,
+			0x01, 0x7c, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn tee_local_wrong_type_failure_binary() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     (local i32)
+		//     f32.const 1
+		//     tee_local 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x0d, 0x01, 0x0b, 0x01, 0x01, 0x7f, 0x43, 0x00, 0x00, 0x80, 0x3f, 0x22,
+			0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		match validator.validate() {
+			Err(LocatedError { function_index: Some(0), instruction_index: Some(1), error: InstructionError::InvalidOperation { .. }, .. }) => (),
+			_ => panic!("expected tee_local to reject an f32 for an i32 local"),
+		}
+	}
+
+	#[test]
+	fn tee_local_empty_stack_failure_binary() {
+		// WAST:
+		// (module
+		//   (func
+		//     (local i32)
+		//     tee_local 0
+		//     drop))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02,
+			0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x01, 0x01, 0x7f, 0x22, 0x00, 0x1a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::StackUnderflow, .. }) => (),
+			_ => panic!("expected tee_local to underflow the stack"),
+		}
+	}
+
+	#[test]
+	fn get_global_instruction_binary() {
+		// get_global 0 refers to the i64 global, not to the f32 parameter
+		// WAST:
+		// (module
+		//   (global i64 (i64.const 5))
+		//   (func (param f32) (result i64)
+		//     get_global 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7d, 0x01, 0x7e,
+			0x03, 0x02, 0x01, 0x00, 0x06, 0x06, 0x01, 0x7e, 0x00, 0x42, 0x05, 0x0b, 0x0a, 0x06, 0x01, 0x04,
+			0x00, 0x23, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn get_global_missing_global_failure_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_global 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x06, 0x01, 0x04, 0x00, 0x23, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::GlobalNotFound, .. }) => (),
+			_ => panic!("expected get_global to fail without a global section"),
+		}
+	}
+
+	#[test]
+	fn set_global_mutable_binary() {
+		// WAST:
+		// (module
+		//   (global (mut f32) (f32.const 0))
+		//   (func (param f32)
+		//     get_local 0
+		//     set_global 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x01, 0x7d, 0x00, 0x03,
+			0x02, 0x01, 0x00, 0x06, 0x09, 0x01, 0x7d, 0x01, 0x43, 0x00, 0x00, 0x00, 0x00, 0x0b, 0x0a, 0x08,
+			0x01, 0x06, 0x00, 0x20, 0x00, 0x24, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn set_global_immutable_failure_binary() {
+		// WAST:
+		// (module
+		//   (global f32 (f32.const 0))
+		//   (func (param f32)
+		//     get_local 0
+		//     set_global 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x01, 0x7d, 0x00, 0x03,
+			0x02, 0x01, 0x00, 0x06, 0x09, 0x01, 0x7d, 0x00, 0x43, 0x00, 0x00, 0x00, 0x00, 0x0b, 0x0a, 0x08,
+			0x01, 0x06, 0x00, 0x20, 0x00, 0x24, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::ImmutableGlobal(0), .. }) => (),
+			_ => panic!("expected set_global to reject an immutable global"),
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn set_global_wrong_type_failure_binary() {
+		// WAST:
+		// (module
+		//   (global (mut f32) (f32.const 0))
+		//   (func (param i32)
+		//     get_local 0
+		//     set_global 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x01, 0x7f, 0x00, 0x03,
+			0x02, 0x01, 0x00, 0x06, 0x09, 0x01, 0x7d, 0x01, 0x43, 0x00, 0x00, 0x00, 0x00, 0x0b, 0x0a, 0x08,
+			0x01, 0x06, 0x00, 0x20, 0x00, 0x24, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn load_instruction_binary() {
+		// WAST:
+		// (module
+		//   (memory 1)
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     i32.load))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x05, 0x03, 0x01, 0x00, 0x01, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00,
+			0x28, 0x02, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	#[should_panic]
+	fn load_wrong_address_failure_binary() {
+		// Addresses are always i32, even when loading a 64 bit value
+		// WAST:
+		// (module
+		//   (memory 1)
+		//   (func (param i64) (result f64)
+		//     get_local 0
+		//     f64.load))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7e, 0x01, 0x7c,
+			0x03, 0x02, 0x01, 0x00, 0x05, 0x03, 0x01, 0x00, 0x01, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00,
+			0x2b, 0x03, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::MEMORY);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn load_without_memory_failure_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     i32.load))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x28, 0x02, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::MEMORY);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::NoMemory, .. }) => (),
+			_ => panic!("expected a load without memory to fail"),
+		}
+	}
+
+	#[test]
+	fn store_instruction_binary() {
+		// WAST:
+		// (module
+		//   (memory 1)
+		//   (func (param i32 f32)
+		//     get_local 0
+		//     get_local 1
+		//     f32.store))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x02, 0x7f, 0x7d, 0x00,
+			0x03, 0x02, 0x01, 0x00, 0x05, 0x03, 0x01, 0x00, 0x01, 0x0a, 0x0b, 0x01, 0x09, 0x00, 0x20, 0x00,
+			0x20, 0x01, 0x38, 0x02, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	#[should_panic]
+	fn store_operand_order_failure_binary() {
+		// The address has to be pushed before the value, so swapping them is rejected
+		// WAST:
+		// (module
+		//   (memory 1)
+		//   (func (param i32 f32)
+		//     get_local 1
+		//     get_local 0
+		//     f32.store))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x02, 0x7f, 0x7d, 0x00,
+			0x03, 0x02, 0x01, 0x00, 0x05, 0x03, 0x01, 0x00, 0x01, 0x0a, 0x0b, 0x01, 0x09, 0x00, 0x20, 0x01,
+			0x20, 0x00, 0x38, 0x02, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::MEMORY);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn grow_memory_instruction_binary() {
+		// WAST:
+		// (module
+		//   (memory 1)
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     grow_memory
+		//     current_memory
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x05, 0x03, 0x01, 0x00, 0x01, 0x0a, 0x0b, 0x01, 0x09, 0x00, 0x20, 0x00,
+			0x40, 0x00, 0x3f, 0x00, 0x6a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn grow_memory_without_memory_failure_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     grow_memory))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x08, 0x01, 0x06, 0x00, 0x20, 0x00, 0x40, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::MEMORY);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::NoMemory, .. }) => (),
+			_ => panic!("expected grow_memory without memory to fail"),
+		}
+	}
+
+	#[test]
+	fn block_result_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     block (result i32)
+		//       get_local 0
+		//       i32.const 1
+		//       i32.add
+		//     end
+		//     i32.const 2
+		//     i32.mul))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x0f, 0x01, 0x0d, 0x00, 0x02, 0x7f, 0x20, 0x00, 0x41, 0x01, 0x6a,
+			0x0b, 0x41, 0x02, 0x6c, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn block_wrong_result_failure_binary() {
+		// The block declares an i32 result but leaves an f32
+		// WAST:
+		// (module
+		//   (func (param f32) (result i32)
+		//     block (result i32)
+		//       get_local 0
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7d, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x02, 0x7f, 0x20, 0x00, 0x0b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONTROL);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::ResultMismatch { expected, found }, .. }) => {
+				assert_eq!(expected, vec![ValueType::I32]);
+				assert_eq!(found, vec![ValueType::F32]);
+			}
+			_ => panic!("expected the block to leave the wrong type"),
+		}
+	}
+
+	#[test]
+	#[should_panic]
+	fn loop_unreachable_scope_binary() {
+		// unreachable only makes the rest of the loop polymorphic, not the code after it
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     loop
+		//       unreachable
+		//     end
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x03, 0x40, 0x00, 0x0b, 0x6a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn if_else_balanced_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32 i64) (result i64)
+		//     get_local 0
+		//     if (result i64)
+		//       get_local 1
+		//     else
+		//       i64.const 0
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7e, 0x01,
+			0x7e, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x0e, 0x01, 0x0c, 0x00, 0x20, 0x00, 0x04, 0x7e, 0x20, 0x01,
+			0x05, 0x42, 0x00, 0x0b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn if_else_unbalanced_failure_binary() {
+		// The else arm produces an f64 where the if declares an i64
+		// WAST:
+		// (module
+		//   (func (param i32 i64) (result i64)
+		//     get_local 0
+		//     if (result i64)
+		//       get_local 1
+		//     else
+		//       f64.const 0
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7e, 0x01,
+			0x7e, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x15, 0x01, 0x13, 0x00, 0x20, 0x00, 0x04, 0x7e, 0x20, 0x01,
+			0x05, 0x44, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONTROL);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::ResultMismatch { expected, found }, .. }) => {
+				assert_eq!(expected, vec![ValueType::I64]);
+				assert_eq!(found, vec![ValueType::F64]);
+			}
+			_ => panic!("expected the else arm to leave the wrong type"),
+		}
+	}
+
+	#[test]
+	fn if_condition_failure_binary() {
+		// WAST:
+		// (module
+		//   (func (param f32)
+		//     get_local 0
+		//     if
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x01, 0x7d, 0x00, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x04, 0x40, 0x0b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONTROL);
+		match validator.validate() {
+			Err(LocatedError { function_index: Some(0), instruction_index: Some(1), error: InstructionError::InvalidOperation { .. }, .. }) => (),
+			_ => panic!("expected if to reject an f32 condition"),
+		}
+	}
+
+	#[test]
+	fn br_instruction_binary() {
+		// The i32 leaves the block through br, so the dead i32.add after it has no real operands
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     block (result i32)
+		//       get_local 0
+		//       br 0
+		//       i32.add
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x0c, 0x01, 0x0a, 0x00, 0x02, 0x7f, 0x20, 0x00, 0x0c, 0x00, 0x6a,
+			0x0b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+	#[test]
+	fn br_if_instruction_binary() {
+		// br_if keeps its label value on the stack when the branch is not taken
+		// WAST:
+		// (module
+		//   (func (param i32 i64) (result i64)
+		//     block (result i64)
+		//       get_local 1
+		//       get_local 0
+		//       br_if 0
+		//       i64.const 1
+		//       i64.add
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7e, 0x01,
+			0x7e, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x10, 0x01, 0x0e, 0x00, 0x02, 0x7e, 0x20, 0x01, 0x20, 0x00,
+			0x0d, 0x00, 0x42, 0x01, 0x7c, 0x0b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn br_wrong_label_type_failure_binary() {
+		// WAST:
+		// (module
+		//   (func (param f32) (result i32)
+		//     block (result i32)
+		//       get_local 0
+		//       br 0
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7d, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x0b, 0x01, 0x09, 0x00, 0x02, 0x7f, 0x20, 0x00, 0x0c, 0x00, 0x0b,
+			0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONTROL);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::ResultMismatch { expected, found }, .. }) => {
+				assert_eq!(expected, vec![ValueType::I32]);
+				assert_eq!(found, vec![ValueType::F32]);
+			}
+			_ => panic!("expected br to supply the wrong label type"),
+		}
+	}
+
+	#[test]
+	fn br_if_out_of_range_failure_binary() {
+		// The text format refuses to assemble an out of range label, so the instruction is patched after parsing
+		// WAST:
+		// (module
+		//   (func (param i32)
+		//     block
+		//       get_local 0
+		//       br_if 1
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x01, 0x7f, 0x00, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x0b, 0x01, 0x09, 0x00, 0x02, 0x40, 0x20, 0x00, 0x0d, 0x01, 0x0b, 0x0b
+		];
+
+		let mut module = deserialize_buffer::<Module>(&wasm).unwrap();
+		// depth 1 is still the function body, so push the branch one label further out than any block
+		let code = module.code_section_mut().unwrap().bodies_mut()[0].code_mut().elements_mut();
+		code[2] = Instruction::BrIf(2);
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONTROL);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::InvalidBranchTarget(2), .. }) => (),
+			_ => panic!("expected br_if to reject a label that does not exist"),
+		}
+	}
+
+	#[test]
+	fn br_table_instruction_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     block (result i32)
+		//       block (result i32)
+		//         block (result i32)
+		//           i32.const 7
+		//           get_local 0
+		//           br_table 0 1 2 1
+		//         end
+		//       end
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x17, 0x01, 0x15, 0x00, 0x02, 0x7f, 0x02, 0x7f, 0x02, 0x7f, 0x41,
+			0x07, 0x20, 0x00, 0x0e, 0x03, 0x00, 0x01, 0x02, 0x01, 0x0b, 0x0b, 0x0b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	#[should_panic]
+	fn br_table_inconsistent_targets_failure_binary() {
+		// The inner block takes no value while the outer one takes an i32, so they cannot share a br_table
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     block (result i32)
+		//       block
+		//         i32.const 7
+		//         get_local 0
+		//         br_table 0 1
+		//       end
+		//       i32.const 0
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x14, 0x01, 0x12, 0x00, 0x02, 0x7f, 0x02, 0x40, 0x41, 0x07, 0x20,
+			0x00, 0x0e, 0x01, 0x00, 0x01, 0x0b, 0x41, 0x00, 0x0b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONTROL);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn br_table_out_of_range_failure_binary() {
+		// The out of range target is patched in after parsing
+		// WAST:
+		// (module
+		//   (func (param i32)
+		//     block
+		//       get_local 0
+		//       br_table 0 1
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x01, 0x7f, 0x00, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x0d, 0x01, 0x0b, 0x00, 0x02, 0x40, 0x20, 0x00, 0x0e, 0x01, 0x00, 0x01,
+			0x0b, 0x0b
+		];
+
+		let mut module = deserialize_buffer::<Module>(&wasm).unwrap();
+		let code = module.code_section_mut().unwrap().bodies_mut()[0].code_mut().elements_mut();
+		code[2] = Instruction::BrTable(Box::new(BrTableData { table: Box::new([0, 5]), default: 1 }));
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONTROL);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::InvalidBranchTarget(5), .. }) => (),
+			_ => panic!("expected br_table to reject a label that does not exist"),
+		}
+	}
+
+	#[test]
+	fn early_return_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     if
+		//       i32.const 1
+		//       return
+		//     end
+		//     i32.const 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x0e, 0x01, 0x0c, 0x00, 0x20, 0x00, 0x04, 0x40, 0x41, 0x01, 0x0f,
+			0x0b, 0x41, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	fn early_return_wrong_type_failure_binary() {
+		// The early return hands back an i64 from a function declared to return i32
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     if
+		//       i64.const 1
+		//       return
+		//     end
+		//     i32.const 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x0e, 0x01, 0x0c, 0x00, 0x20, 0x00, 0x04, 0x40, 0x42, 0x01, 0x0f,
+			0x0b, 0x41, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONTROL);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::ResultMismatch { expected, found }, .. }) => {
+				assert_eq!(expected, vec![ValueType::I32]);
+				assert_eq!(found, vec![ValueType::I64]);
+			}
+			_ => panic!("expected return to supply the wrong type"),
+		}
+	}
+
+	#[test]
+	fn call_instruction_binary() {
+		// WAST:
+		// (module
+		//   (func $add (param i32 i32) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     i32.add)
+		//   (func $main (result i32)
+		//     i32.const 1
+		//     i32.const 2
+		//     call $add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0b, 0x02, 0x60, 0x02, 0x7f, 0x7f, 0x01,
+			0x7f, 0x60, 0x00, 0x01, 0x7f, 0x03, 0x03, 0x02, 0x00, 0x01, 0x0a, 0x12, 0x02, 0x07, 0x00, 0x20,
+			0x00, 0x20, 0x01, 0x6a, 0x0b, 0x08, 0x00, 0x41, 0x01, 0x41, 0x02, 0x10, 0x00, 0x0b, 0x00, 0x13,
+			0x04, 0x6e, 0x61, 0x6d, 0x65, 0x01, 0x0c, 0x02, 0x00, 0x03, 0x61, 0x64, 0x64, 0x01, 0x04, 0x6d,
+			0x61, 0x69, 0x6e
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	#[should_panic]
+	fn call_wrong_argument_failure_binary() {
+		// WAST:
+		// (module
+		//   (func $add (param i32 i32) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     i32.add)
+		//   (func $main (result i32)
+		//     i32.const 1
+		//     f32.const 2
+		//     call $add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0b, 0x02, 0x60, 0x02, 0x7f, 0x7f, 0x01,
+			0x7f, 0x60, 0x00, 0x01, 0x7f, 0x03, 0x03, 0x02, 0x00, 0x01, 0x0a, 0x15, 0x02, 0x07, 0x00, 0x20,
+			0x00, 0x20, 0x01, 0x6a, 0x0b, 0x0b, 0x00, 0x41, 0x01, 0x43, 0x00, 0x00, 0x00, 0x40, 0x10, 0x00,
+			0x0b, 0x00, 0x13, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x01, 0x0c, 0x02, 0x00, 0x03, 0x61, 0x64, 0x64,
+			0x01, 0x04, 0x6d, 0x61, 0x69, 0x6e
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONTROL);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn call_missing_function_failure_binary() {
+		// The call is patched to point past the only function after parsing
+		// WAST:
+		// (module
+		//   (func $main
+		//     call $main))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02,
+			0x01, 0x00, 0x0a, 0x06, 0x01, 0x04, 0x00, 0x10, 0x00, 0x0b, 0x00, 0x0e, 0x04, 0x6e, 0x61, 0x6d,
+			0x65, 0x01, 0x07, 0x01, 0x00, 0x04, 0x6d, 0x61, 0x69, 0x6e
+		];
+
+		let mut module = deserialize_buffer::<Module>(&wasm).unwrap();
+		let code = module.code_section_mut().unwrap().bodies_mut()[0].code_mut().elements_mut();
+		code[0] = Instruction::Call(3);
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONTROL);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::FunctionNotFound(3), .. }) => (),
+			_ => panic!("expected call to reject a function that does not exist"),
+		}
+	}
+
+	#[test]
+	fn call_indirect_instruction_binary() {
+		// WAST:
+		// (module
+		//   (type $binop (func (param i64 i64) (result i64)))
+		//   (table 1 anyfunc)
+		//   (func (param i64 i32) (result i64)
+		//     get_local 0
+		//     get_local 0
+		//     get_local 1
+		//     call_indirect (type $binop)))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0d, 0x02, 0x60, 0x02, 0x7e, 0x7e, 0x01,
+			0x7e, 0x60, 0x02, 0x7e, 0x7f, 0x01, 0x7e, 0x03, 0x02, 0x01, 0x01, 0x04, 0x04, 0x01, 0x70, 0x00,
+			0x01, 0x0a, 0x0d, 0x01, 0x0b, 0x00, 0x20, 0x00, 0x20, 0x00, 0x20, 0x01, 0x11, 0x00, 0x00, 0x0b,
+			0x00, 0x0f, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x04, 0x08, 0x01, 0x00, 0x05, 0x62, 0x69, 0x6e, 0x6f,
+			0x70
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
+
+	#[test]
+	#[should_panic]
+	fn call_indirect_selector_failure_binary() {
+		// The table index has to be an i32
+		// WAST:
+		// (module
+		//   (type $binop (func (param i64 i64) (result i64)))
+		//   (table 1 anyfunc)
+		//   (func (param i64) (result i64)
+		//     get_local 0
+		//     get_local 0
+		//     get_local 0
+		//     call_indirect (type $binop)))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0c, 0x02, 0x60, 0x02, 0x7e, 0x7e, 0x01,
+			0x7e, 0x60, 0x01, 0x7e, 0x01, 0x7e, 0x03, 0x02, 0x01, 0x01, 0x04, 0x04, 0x01, 0x70, 0x00, 0x01,
+			0x0a, 0x0d, 0x01, 0x0b, 0x00, 0x20, 0x00, 0x20, 0x00, 0x20, 0x00, 0x11, 0x00, 0x00, 0x0b, 0x00,
+			0x0f, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x04, 0x08, 0x01, 0x00, 0x05, 0x62, 0x69, 0x6e, 0x6f, 0x70
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONTROL);
+		validator.validate().unwrap();
+	}
+
+	#[test]
+	fn call_indirect_without_table_failure_binary() {
+		// WAST:
+		// (module
+		//   (type $binop (func (param i64 i64) (result i64)))
+		//   (func (param i64 i32) (result i64)
+		//     get_local 0
+		//     get_local 0
+		//     get_local 1
+		//     call_indirect (type $binop)))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0d, 0x02, 0x60, 0x02, 0x7e, 0x7e, 0x01,
+			0x7e, 0x60, 0x02, 0x7e, 0x7f, 0x01, 0x7e, 0x03, 0x02, 0x01, 0x01, 0x0a, 0x0d, 0x01, 0x0b, 0x00,
+			0x20, 0x00, 0x20, 0x00, 0x20, 0x01, 0x11, 0x00, 0x00, 0x0b, 0x00, 0x0f, 0x04, 0x6e, 0x61, 0x6d,
+			0x65, 0x04, 0x08, 0x01, 0x00, 0x05, 0x62, 0x69, 0x6e, 0x6f, 0x70
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONTROL);
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::NoTable, .. }) => (),
+			_ => panic!("expected call_indirect without a table to fail"),
+		}
+	}
+
+	#[test]
+	fn validate_all_reports_every_function() {
+		// The first and last functions each contain a type error, the one in between is fine
+		// WAST:
+		// (module
+		//   (func (param i32 f32) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     i32.add)
+		//   (func (param i32) (result i32)
+		//     get_local 0)
+		//   (func (param i64) (result i64)
+		//     get_local 0
+		//     f64.neg))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x11, 0x03, 0x60, 0x02, 0x7f, 0x7d, 0x01,
+			0x7f, 0x60, 0x01, 0x7f, 0x01, 0x7f, 0x60, 0x01, 0x7e, 0x01, 0x7e, 0x03, 0x04, 0x03, 0x00, 0x01,
+			0x02, 0x0a, 0x14, 0x03, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b, 0x04, 0x00, 0x20, 0x00,
+			0x0b, 0x05, 0x00, 0x20, 0x00, 0x9a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let errors = validator.validate_all();
+		assert_eq!(errors.len(), 2);
+		match &errors[0] {
+			LocatedError { function_index: Some(0), instruction_index: Some(2), error: InstructionError::InvalidOperation { .. }, .. } => (),
+			_ => panic!("expected i32.add in the first function to fail"),
+		}
+		match &errors[1] {
+			LocatedError { function_index: Some(2), instruction_index: Some(1), error: InstructionError::InvalidOperation { .. }, .. } => (),
+			_ => panic!("expected f64.neg in the last function to fail"),
+		}
+	}
+
+	#[test]
+	fn validate_all_valid_module() {
+		// WAST:
+		// (module
+		//   (func (param i32 i32) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     i32.sub))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7f, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		assert!(validator.validate_all().is_empty())
+	}
+
+	#[test]
+	fn located_error_binary() {
+		// The i64.mul in the second function gets an i32 operand
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 0)
+		//   (func (param i32 i64) (result i64)
+		//     get_local 1
+		//     get_local 1
+		//     i64.add
+		//     get_local 0
+		//     i64.mul))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0c, 0x02, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x60, 0x02, 0x7f, 0x7e, 0x01, 0x7e, 0x03, 0x03, 0x02, 0x00, 0x01, 0x0a, 0x11, 0x02, 0x04, 0x00,
+			0x20, 0x00, 0x0b, 0x0a, 0x00, 0x20, 0x01, 0x20, 0x01, 0x7c, 0x20, 0x00, 0x7e, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.function_index, Some(1));
+		assert_eq!(error.instruction_index, Some(4));
+	}
+
+	#[test]
+	fn instruction_error_display() {
+		assert_eq!(InstructionError::LocalNotFound.to_string(), "local not found");
+		let error = InstructionError::InvalidOperation { expected: ValueType::I32, found: ValueType::F64 };
+		assert_eq!(error.to_string(), "invalid operation: expected i32, found f64");
+		let error = InstructionError::ResultMismatch { expected: vec![ValueType::I32], found: vec![ValueType::I32, ValueType::F64] };
+		assert_eq!(error.to_string(), "result mismatch: expected [i32], found [i32, f64]");
+		let error = InstructionError::BranchTargetMismatch { target: 1, got: vec![], expected: vec![ValueType::I64] };
+		assert_eq!(error.to_string(), "branch target 1 takes [], but the default target takes [i64]");
+		let error = InstructionError::NonEmptyStack { leftover: vec![ValueType::F32, ValueType::I32] };
+		assert_eq!(error.to_string(), "function without results leaves [f32, i32] on the stack");
+	}
+
+	#[test]
+	fn located_error_as_boxed_error() {
+		fn validate(module: &Module) -> Result<bool, Box<dyn std::error::Error>> {
+			Ok(ModuleValidator::new(module, Filter::NUMERIC).validate()?)
+		}
+
+		let module = deserialize_file("./src/wasm_binaries/test.wasm").unwrap();
+		assert!(validate(&module).unwrap());
+
+		let error = LocatedError::new(0, 2, InstructionError::StackUnderflow);
+		assert_eq!(error.to_string(), "function 0, instruction 2: stack underflow");
+
+		// error chains reach the instruction error through `source`
+		let source = std::error::Error::source(&error).unwrap();
+		assert_eq!(source.to_string(), "stack underflow");
+	}
+
+	#[test]
+	fn all_filters_type_mismatch() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1
+		//     i64.const 2
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x41, 0x01, 0x42, 0x02, 0x6a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+		let validator = ModuleValidator::new(&module, Filter::all());
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::InvalidOperation { .. }, .. }) => (),
+			_ => panic!("Filter::all() should reject i32.add on an i64 operand"),
+		}
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		assert!(validator.validate().is_err());
+	}
+	#[test]
+	fn complex_instruction_report() {
+		let module = deserialize_buffer::<Module>(COMPLEX_INSTRUCTION_WASM).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let report = validator.validate_report();
+		assert!(report.valid);
+		assert_eq!(report.functions_checked, 3);
+		assert_eq!(report.instructions_checked, 12);
+		assert_eq!(report.per_function.len(), 3);
+		assert!(report.per_function.iter().all(|function| function.valid && function.errors.is_empty()));
+	}
+	#[test]
+	fn simple_instructions_bytes() {
+		// WAST:
+		// (module
+		//   (type $t0 (func (param i32 i32) (result i32)))
+		//   (func $f0 (type $t0) (param $p0 i32) (param $p1 i32) (result i32)
+		//     (i32.add
+		//       (get_local $p0)
+		//       (get_local $p1))))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7f, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b,
+			0x00, 0x14, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x02, 0x0d, 0x01, 0x00, 0x02, 0x00, 0x03, 0x6c, 0x68,
+			0x73, 0x01, 0x03, 0x72, 0x68, 0x73
+		];
+
+		assert!(validate_bytes(&wasm, Filter::NUMERIC).unwrap());
+	}
+
+	#[test]
+	fn invalid_bytes() {
+		let wasm: Vec<u8> = vec![0x00, 0x61, 0x73, 0x6d, 0x02];
+
+		let error = match validate_bytes(&wasm, Filter::NUMERIC) {
+			Err(error @ ValidationError::Deserialize(_)) => error,
+			_ => panic!("a truncated header should fail to deserialize"),
+		};
+		// the deserializer's own error is the source
+		assert!(std::error::Error::source(&error).is_some());
+	}
+	#[test]
+	fn validate_test_file() {
+		assert!(validate_file("./src/wasm_binaries/test.wasm", Filter::NUMERIC).unwrap());
+	}
+
+	#[test]
+	fn validate_missing_file() {
+		match validate_file("./src/wasm_binaries/missing.wasm", Filter::NUMERIC) {
+			Err(error @ ValidationError::Io(_)) => assert!(std::error::Error::source(&error).is_some()),
+			_ => panic!("a missing file should be reported as an IO error"),
+		}
+	}
+	#[test]
+	fn thousands_of_instructions() {
+		let mut instructions = vec![Instruction::I32Const(0)];
+		for _ in 0..5000 {
+			instructions.push(Instruction::I32Const(1));
+			instructions.push(Instruction::I32Add);
+		}
+		instructions.push(Instruction::End);
+
+		let module = Module::new(vec![
+			Section::Type(TypeSection::with_types(vec![Type::Function(FunctionType::new(vec![], Some(ValueType::I32)))])),
+			Section::Function(FunctionSection::with_entries(vec![Func::new(0)])),
+			Section::Code(CodeSection::with_bodies(vec![FuncBody::new(vec![], Instructions::new(instructions))])),
+		]);
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let report = validator.validate_report();
+		assert!(report.valid);
+		assert_eq!(report.instructions_checked, 10002);
+	}
+
+	#[test]
+	fn invalid_operation_indices() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1)
+		//   (func (result i64)
+		//     i64.const 1
+		//     i64.const 2
+		//     f32.const 3
+		//     i64.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x09, 0x02, 0x60, 0x00, 0x01, 0x7f, 0x60,
+			0x00, 0x01, 0x7e, 0x03, 0x03, 0x02, 0x00, 0x01, 0x0a, 0x13, 0x02, 0x04, 0x00, 0x41, 0x01, 0x0b,
+			0x0c, 0x00, 0x42, 0x01, 0x42, 0x02, 0x43, 0x00, 0x00, 0x40, 0x40, 0x7c, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let error = validator.validate().unwrap_err();
+		assert_eq!((error.function_index, error.instruction_index, &error.error), (Some(1), Some(3), &InstructionError::InvalidOperation { expected: ValueType::I64, found: ValueType::F32 }));
+	}
+	#[test]
+	fn builder_validator() {
+		// WAST:
+		// (module
+		//   (type $t0 (func (param i32 i32) (result i32)))
+		//   (func $f0 (type $t0) (param $p0 i32) (param $p1 i32) (result i32)
+		//     (i32.add
+		//       (get_local $p0)
+		//       (get_local $p1))))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7f, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b,
+			0x00, 0x14, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x02, 0x0d, 0x01, 0x00, 0x02, 0x00, 0x03, 0x6c, 0x68,
+			0x73, 0x01, 0x03, 0x72, 0x68, 0x73
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidatorBuilder::new(&module)
+			.filter(Filter::NUMERIC)
+			.max_stack_height(2)
+			.strict(true)
+			.build();
+		assert!(validator.validate().unwrap());
+
+		let validator = ModuleValidatorBuilder::new(&module).max_stack_height(1).build();
+		match validator.validate() {
+			Err(LocatedError { instruction_index: Some(1), error: InstructionError::StackOverflow(1), .. }) => (),
+			_ => panic!("two locals on the stack should exceed a limit of one"),
+		}
+	}
+
+	#[test]
+	fn numeric_and_memory_filter() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     i32.wrap/i64
+		//     i32.const 0
+		//     i32.load
+		//     i32.add)
+		//   (memory 1))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x05, 0x03, 0x01, 0x00, 0x01, 0x0a, 0x0d, 0x01, 0x0b, 0x00, 0x20, 0x00,
+			0xa7, 0x41, 0x00, 0x28, 0x02, 0x00, 0x6a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// the conversion is skipped, so its bad operand goes unnoticed
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::MEMORY);
+		assert!(validator.validate().unwrap());
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC | Filter::CONVERSION);
+		match validator.validate() {
+			Err(LocatedError { instruction_index: Some(1), error: InstructionError::InvalidOperation { .. }, .. }) => (),
+			_ => panic!("i32.wrap/i64 should reject an i32 operand"),
+		}
+	}
+
+	#[test]
+	fn i32_const_signature() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1
+		//     i32.eqz)
+		//   (func (result i32)
+		//     i32.const 1
+		//     i64.eqz))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x03, 0x02, 0x00, 0x00, 0x0a, 0x0d, 0x02, 0x05, 0x00, 0x41, 0x01, 0x45, 0x0b, 0x05, 0x00, 0x41,
+			0x01, 0x50, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let errors = validator.validate_all();
+		assert_eq!(errors.len(), 1);
+		assert_eq!((errors[0].function_index, errors[0].instruction_index, &errors[0].error), (Some(1), Some(1), &InstructionError::InvalidOperation { expected: ValueType::I64, found: ValueType::I32 }));
+	}
+
+	#[test]
+	fn i64_const_signature() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i64.const 1
+		//     i64.eqz)
+		//   (func (result i32)
+		//     i64.const 1
+		//     i32.eqz))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x03, 0x02, 0x00, 0x00, 0x0a, 0x0d, 0x02, 0x05, 0x00, 0x42, 0x01, 0x50, 0x0b, 0x05, 0x00, 0x42,
+			0x01, 0x45, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let errors = validator.validate_all();
+		assert_eq!(errors.len(), 1);
+		assert_eq!((errors[0].function_index, errors[0].instruction_index, &errors[0].error), (Some(1), Some(1), &InstructionError::InvalidOperation { expected: ValueType::I32, found: ValueType::I64 }));
+	}
+
+	#[test]
+	fn f32_const_signature() {
+		// WAST:
+		// (module
+		//   (func (result f32)
+		//     f32.const 1
+		//     f32.neg)
+		//   (func (result f64)
+		//     f32.const 1
+		//     f64.neg))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x09, 0x02, 0x60, 0x00, 0x01, 0x7d, 0x60,
+			0x00, 0x01, 0x7c, 0x03, 0x03, 0x02, 0x00, 0x01, 0x0a, 0x13, 0x02, 0x08, 0x00, 0x43, 0x00, 0x00,
+			0x80, 0x3f, 0x8c, 0x0b, 0x08, 0x00, 0x43, 0x00, 0x00, 0x80, 0x3f, 0x9a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let errors = validator.validate_all();
+		assert_eq!(errors.len(), 1);
+		assert_eq!((errors[0].function_index, errors[0].instruction_index, &errors[0].error), (Some(1), Some(1), &InstructionError::InvalidOperation { expected: ValueType::F64, found: ValueType::F32 }));
+	}
+
+	#[test]
+	fn f64_const_signature() {
+		// WAST:
+		// (module
+		//   (func (result f64)
+		//     f64.const 1
+		//     f64.neg)
+		//   (func (result f32)
+		//     f64.const 1
+		//     f32.neg))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x09, 0x02, 0x60, 0x00, 0x01, 0x7c, 0x60,
+			0x00, 0x01, 0x7d, 0x03, 0x03, 0x02, 0x00, 0x01, 0x0a, 0x1b, 0x02, 0x0c, 0x00, 0x44, 0x00, 0x00,
+			0x00, 0x00, 0x00, 0x00, 0xf0, 0x3f, 0x9a, 0x0b, 0x0c, 0x00, 0x44, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0x00, 0xf0, 0x3f, 0x8c, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let errors = validator.validate_all();
+		assert_eq!(errors.len(), 1);
+		assert_eq!((errors[0].function_index, errors[0].instruction_index, &errors[0].error), (Some(1), Some(1), &InstructionError::InvalidOperation { expected: ValueType::F32, found: ValueType::F64 }));
+	}
+
+	#[test]
+	fn max_stack_height_overflow() {
+		// WAST:
+		// (module
+		//   (func
+		//     i32.const 1
+		//     i32.const 2
+		//     i32.const 3
+		//     i32.const 4
+		//     i32.const 5
+		//     drop
+		//     drop
+		//     drop
+		//     drop
+		//     drop))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02,
+			0x01, 0x00, 0x0a, 0x13, 0x01, 0x11, 0x00, 0x41, 0x01, 0x41, 0x02, 0x41, 0x03, 0x41, 0x04, 0x41,
+			0x05, 0x1a, 0x1a, 0x1a, 0x1a, 0x1a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidatorBuilder::new(&module).max_stack_height(4).build();
+		match validator.validate() {
+			Err(LocatedError { instruction_index: Some(4), error: InstructionError::StackOverflow(4), .. }) => (),
+			_ => panic!("a fifth const should overflow a stack limited to four values"),
+		}
+
+		let validator = ModuleValidatorBuilder::new(&module).max_stack_height(5).build();
+		assert!(validator.validate().unwrap());
+	}
+
+	#[test]
+	fn binop_stack_underflow_failure() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x07, 0x01, 0x05, 0x00, 0x41, 0x01, 0x6a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let error = validator.validate().unwrap_err();
+		assert_eq!((error.instruction_index, error.error), (Some(1), InstructionError::StackUnderflow));
+	}
+
+	#[test]
+	fn binop_operand_type_failure() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1
+		//     f32.const 2
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x0c, 0x01, 0x0a, 0x00, 0x41, 0x01, 0x43, 0x00, 0x00, 0x00, 0x40, 0x6a,
+			0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let error = validator.validate().unwrap_err();
+		assert_eq!((error.function_index, error.instruction_index, &error.error), (Some(0), Some(2), &InstructionError::InvalidOperation { expected: ValueType::I32, found: ValueType::F32 }));
+	}
+	#[test]
+	fn missing_type_section_failure() {
+		let module = Module::new(vec![
+			Section::Function(FunctionSection::with_entries(vec![Func::new(0)])),
+			Section::Code(CodeSection::with_bodies(vec![FuncBody::new(vec![], Instructions::new(vec![Instruction::End]))])),
+		]);
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::MalformedModule, .. }) => (),
+			_ => panic!("a body without a type section should be reported as malformed"),
+		}
+	}
+	#[test]
+	fn type_ref_out_of_range_failure() {
+		let module = Module::new(vec![
+			Section::Type(TypeSection::with_types(vec![Type::Function(FunctionType::new(vec![], None))])),
+			Section::Function(FunctionSection::with_entries(vec![Func::new(5)])),
+			Section::Code(CodeSection::with_bodies(vec![FuncBody::new(vec![], Instructions::new(vec![Instruction::End]))])),
+		]);
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::TypeNotFound(5), .. }) => (),
+			_ => panic!("a type_ref past the end of the type section should be reported"),
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "sign_ext")]
+	fn sign_ext_8_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     i32.extend8_s)
+		//   (func (param i64) (result i64)
+		//     get_local 0
+		//     i64.extend8_s)
+		//   (func (param i64) (result i32)
+		//     get_local 0
+		//     i32.extend8_s))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x10, 0x03, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x60, 0x01, 0x7e, 0x01, 0x7e, 0x60, 0x01, 0x7e, 0x01, 0x7f, 0x03, 0x04, 0x03, 0x00, 0x01, 0x02,
+			0x0a, 0x13, 0x03, 0x05, 0x00, 0x20, 0x00, 0xc0, 0x0b, 0x05, 0x00, 0x20, 0x00, 0xc2, 0x0b, 0x05,
+			0x00, 0x20, 0x00, 0xc0, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let errors = validator.validate_all();
+		assert_eq!(errors.len(), 1);
+		assert_eq!((errors[0].function_index, errors[0].instruction_index, &errors[0].error), (Some(2), Some(1), &InstructionError::InvalidOperation { expected: ValueType::I32, found: ValueType::I64 }));
+	}
+
+	#[test]
+	#[cfg(feature = "sign_ext")]
+	fn sign_ext_16_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     i32.extend16_s)
+		//   (func (param i64) (result i64)
+		//     get_local 0
+		//     i64.extend16_s)
+		//   (func (param i32) (result i64)
+		//     get_local 0
+		//     i64.extend16_s))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x10, 0x03, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x60, 0x01, 0x7e, 0x01, 0x7e, 0x60, 0x01, 0x7f, 0x01, 0x7e, 0x03, 0x04, 0x03, 0x00, 0x01, 0x02,
+			0x0a, 0x13, 0x03, 0x05, 0x00, 0x20, 0x00, 0xc1, 0x0b, 0x05, 0x00, 0x20, 0x00, 0xc3, 0x0b, 0x05,
+			0x00, 0x20, 0x00, 0xc3, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let errors = validator.validate_all();
+		assert_eq!(errors.len(), 1);
+		assert_eq!((errors[0].function_index, errors[0].instruction_index, &errors[0].error), (Some(2), Some(1), &InstructionError::InvalidOperation { expected: ValueType::I64, found: ValueType::I32 }));
+	}
+
+	#[test]
+	#[cfg(feature = "sign_ext")]
+	fn sign_ext_32_binary() {
+		// WAST:
+		// (module
+		//   (func (param i64) (result i64)
+		//     get_local 0
+		//     i64.extend32_s)
+		//   (func (param i32) (result i64)
+		//     get_local 0
+		//     i64.extend32_s))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0b, 0x02, 0x60, 0x01, 0x7e, 0x01, 0x7e,
+			0x60, 0x01, 0x7f, 0x01, 0x7e, 0x03, 0x03, 0x02, 0x00, 0x01, 0x0a, 0x0d, 0x02, 0x05, 0x00, 0x20,
+			0x00, 0xc4, 0x0b, 0x05, 0x00, 0x20, 0x00, 0xc4, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let errors = validator.validate_all();
+		assert_eq!(errors.len(), 1);
+		assert_eq!((errors[0].function_index, errors[0].instruction_index, &errors[0].error), (Some(1), Some(1), &InstructionError::InvalidOperation { expected: ValueType::I64, found: ValueType::I32 }));
+	}
+
+	#[test]
+	fn stack_before_failure() {
+		// WAST:
+		// (module
+		//   (func (param i32 f64) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7c, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.instruction_index, Some(2));
+		assert_eq!(error.stack_before, vec![ValueType::I32, ValueType::F64]);
+	}
+
+	#[test]
+	fn stack_before_popped_failure() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i64.const 7
+		//     i32.const 1
+		//     f32.const 2
+		//     i32.const 0
+		//     select
+		//     drop
+		//     i32.const 3))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x13, 0x01, 0x11, 0x00, 0x42, 0x07, 0x41, 0x01, 0x43, 0x00, 0x00, 0x00,
+			0x40, 0x41, 0x00, 0x1b, 0x1a, 0x41, 0x03, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// select pops all three of its operands before it finds they don't fit
+		let error = ModuleValidator::new(&module, Filter::all()).validate().unwrap_err();
+		assert_eq!(error.instruction_index, Some(4));
+		assert_eq!(error.stack_before, vec![ValueType::I64, ValueType::I32, ValueType::F32, ValueType::I32]);
+	}
+
+	#[test]
+	fn local_not_found_equality() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 1))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x06, 0x01, 0x04, 0x00, 0x20, 0x01, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.error, InstructionError::LocalNotFound);
+		assert_eq!(error, LocatedError::new(0, 0, InstructionError::LocalNotFound).with_byte_offset(Some(25)));
+		assert_ne!(error.error, InstructionError::GlobalNotFound);
+	}
+	#[test]
+	fn complex_instruction_single_function() {
+		let module = deserialize_buffer::<Module>(COMPLEX_INSTRUCTION_WASM).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert!(validator.validate_function(2).unwrap());
+		assert_eq!(validator.validate_function(3), Err(InstructionError::FunctionNotFound(3)));
+	}
+
+	#[test]
+	fn imported_and_defined_globals_binary() {
+		// WAST:
+		// (module
+		//   (import "env" "imported" (global i64))
+		//   (global (mut f32) (f32.const 0))
+		//   (func (result i64)
+		//     get_global 1
+		//     set_global 1
+		//     get_global 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7e, 0x02,
+			0x11, 0x01, 0x03, 0x65, 0x6e, 0x76, 0x08, 0x69, 0x6d, 0x70, 0x6f, 0x72, 0x74, 0x65, 0x64, 0x03,
+			0x7e, 0x00, 0x03, 0x02, 0x01, 0x00, 0x06, 0x09, 0x01, 0x7d, 0x01, 0x43, 0x00, 0x00, 0x00, 0x00,
+			0x0b, 0x0a, 0x0a, 0x01, 0x08, 0x00, 0x23, 0x01, 0x24, 0x01, 0x23, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert!(validator.validate().unwrap());
+	}
+
+	#[test]
+	fn defined_global_after_import_failure_binary() {
+		// WAST:
+		// (module
+		//   (import "env" "imported" (global i64))
+		//   (global (mut f32) (f32.const 0))
+		//   (func (result f32)
+		//     get_global 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7d, 0x02,
+			0x11, 0x01, 0x03, 0x65, 0x6e, 0x76, 0x08, 0x69, 0x6d, 0x70, 0x6f, 0x72, 0x74, 0x65, 0x64, 0x03,
+			0x7e, 0x00, 0x03, 0x02, 0x01, 0x00, 0x06, 0x09, 0x01, 0x7d, 0x01, 0x43, 0x00, 0x00, 0x00, 0x00,
+			0x0b, 0x0a, 0x06, 0x01, 0x04, 0x00, 0x23, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// index 0 is the imported i64, the defined f32 global is index 1
+		let validator = ModuleValidator::new(&module, Filter::all());
+		match validator.validate() {
+			Err(LocatedError { error: InstructionError::ResultMismatch { .. }, .. }) => (),
+			_ => panic!("get_global 0 should read the imported i64 global"),
+		}
+	}
+
+	#[test]
+	fn imported_and_defined_function_calls_binary() {
+		// WAST:
+		// (module
+		//   (import "env" "imported" (func (param i64) (result f32)))
+		//   (func (param i32) (result i32)
+		//     get_local 0)
+		//   (func (result f32)
+		//     i32.const 1
+		//     call 1
+		//     drop
+		//     i64.const 2
+		//     call 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0f, 0x03, 0x60, 0x01, 0x7e, 0x01, 0x7d,
+			0x60, 0x01, 0x7f, 0x01, 0x7f, 0x60, 0x00, 0x01, 0x7d, 0x02, 0x10, 0x01, 0x03, 0x65, 0x6e, 0x76,
+			0x08, 0x69, 0x6d, 0x70, 0x6f, 0x72, 0x74, 0x65, 0x64, 0x00, 0x00, 0x03, 0x03, 0x02, 0x01, 0x02,
+			0x0a, 0x12, 0x02, 0x04, 0x00, 0x20, 0x00, 0x0b, 0x0b, 0x00, 0x41, 0x01, 0x10, 0x01, 0x1a, 0x42,
+			0x02, 0x10, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert!(validator.validate().unwrap());
+	}
+
+	#[test]
+	fn imported_function_call_failure_binary() {
+		// WAST:
+		// (module
+		//   (import "env" "imported" (func (param i64) (result f32)))
+		//   (func (param i32) (result i32)
+		//     get_local 0)
+		//   (func (result i32)
+		//     i32.const 1
+		//     call 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0f, 0x03, 0x60, 0x01, 0x7e, 0x01, 0x7d,
+			0x60, 0x01, 0x7f, 0x01, 0x7f, 0x60, 0x00, 0x01, 0x7f, 0x02, 0x10, 0x01, 0x03, 0x65, 0x6e, 0x76,
+			0x08, 0x69, 0x6d, 0x70, 0x6f, 0x72, 0x74, 0x65, 0x64, 0x00, 0x00, 0x03, 0x03, 0x02, 0x01, 0x02,
+			0x0a, 0x0d, 0x02, 0x04, 0x00, 0x20, 0x00, 0x0b, 0x06, 0x00, 0x41, 0x01, 0x10, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// index 0 is the imported function, which takes an i64
+		let validator = ModuleValidator::new(&module, Filter::all());
+		match validator.validate() {
+			Err(LocatedError { function_index: Some(1), instruction_index: Some(1), error: InstructionError::InvalidOperation { .. }, .. }) => (),
+			_ => panic!("call 0 should be checked against the imported function's type"),
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn serialize_failing_report() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1)
+		//   (func (result i32)
+		//     f32.const 1))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x03, 0x02, 0x00, 0x00, 0x0a, 0x0e, 0x02, 0x04, 0x00, 0x41, 0x01, 0x0b, 0x07, 0x00, 0x43, 0x00,
+			0x00, 0x80, 0x3f, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let json = serde_json::to_string(&validator.validate_report()).unwrap();
+		assert!(json.contains("\"valid\":false"));
+		assert!(json.contains("\"function_index\":1"));
+		assert!(json.contains("\"ResultMismatch\":{\"expected\":[\"i32\"],\"found\":[\"f32\"]}"));
+	}
+	#[test]
+	#[cfg(feature = "wat")]
+	fn simple_instructions_wat() {
+		// the wat crate only understands the current instruction names, e.g. local.get rather than get_local
+		let source = "(module (func (param i32 i32) (result i32) local.get 0 local.get 1 i32.add))";
+		assert!(validate_wat(source, Filter::all()).unwrap());
+
+		let source = "(module (func (param i32 i64) (result i32) local.get 0 local.get 1 i32.add))";
+		match validate_wat(source, Filter::all()) {
+			Err(ValidationError::Instruction(LocatedError { instruction_index: Some(2), .. })) => (),
+			_ => panic!("i32.add should reject an i64 operand"),
+		}
+
+		match validate_wat("(module (func", Filter::all()) {
+			Err(ValidationError::Wat(_)) => (),
+			_ => panic!("unbalanced parentheses should fail to assemble"),
+		}
+	}
+	#[test]
+	fn unmatched_type_function_name() {
+		// WAST:
+		// (module
+		//   (func $addTwo (param f64 i32) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     i32.add)
+		//   (export "addTwo" (func $addTwo)))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7c, 0x7f, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x07, 0x0a, 0x01, 0x06, 0x61, 0x64, 0x64, 0x54, 0x77, 0x6f, 0x00,
+			0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b, 0x00, 0x19, 0x04, 0x6e,
+			0x61, 0x6d, 0x65, 0x01, 0x09, 0x01, 0x00, 0x06, 0x61, 0x64, 0x64, 0x54, 0x77, 0x6f, 0x02, 0x07,
+			0x01, 0x00, 0x02, 0x00, 0x00, 0x01, 0x00
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.function_name, Some("addTwo".to_string()));
+		assert!(error.to_string().starts_with("function 0 (`addTwo`), instruction 2: "));
+	}
+
+	#[test]
+	fn set_local_name_failure() {
+		// WAST:
+		// (module
+		//   (func $addTwo (param $lhs f64) (param $rhs i32) (result i32)
+		//     f32.const 1
+		//     set_local $rhs
+		//     i32.const 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7c, 0x7f, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x0d, 0x01, 0x0b, 0x00, 0x43, 0x00, 0x00, 0x80, 0x3f, 0x21,
+			0x01, 0x41, 0x00, 0x0b, 0x00, 0x1f, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x01, 0x09, 0x01, 0x00, 0x06,
+			0x61, 0x64, 0x64, 0x54, 0x77, 0x6f, 0x02, 0x0d, 0x01, 0x00, 0x02, 0x00, 0x03, 0x6c, 0x68, 0x73,
+			0x01, 0x03, 0x72, 0x68, 0x73
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.local_name, Some("rhs".to_string()));
+		assert!(error.to_string().starts_with("function 0 (`addTwo`), instruction 1, local `rhs`: "));
+	}
+
+	#[test]
+	fn oversized_name_section_failure() {
+		// WAST:
+		// (module (func (result i32)))
+		// followed by a `name` section whose function names claim 2^32 - 1 entries
+		let mut wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b, 0x00, 0x0c, 0x04, 0x6e, 0x61, 0x6d, 0x65,
+			0x01, 0x05, 0xff, 0xff, 0xff, 0xff, 0x0f
+		];
+
+		// the names are only read to describe the error, and then left out rather than trusted
+		let error = validate_bytes(&wasm, Filter::all()).unwrap_err();
+		match error {
+			ValidationError::Instruction(located) => assert_eq!(located.function_name, None),
+			_ => panic!("expected the function's own error"),
+		}
+
+		// the same for a function in the local names claiming 2^32 - 1 locals
+		wasm.truncate(25);
+		wasm.extend(&[0x00, 0x0e, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x02, 0x07, 0x01, 0x00, 0xff, 0xff, 0xff, 0xff, 0x0f]);
+		assert!(validate_bytes(&wasm, Filter::all()).is_err());
+	}
+
+	#[test]
+	fn block_extra_value_failure_binary() {
+		// WAST:
+		// (module
+		//   (func
+		//     block
+		//       i32.const 1
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02,
+			0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x02, 0x40, 0x41, 0x01, 0x0b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.instruction_index, Some(2));
+		assert_eq!(error.error, InstructionError::ResultMismatch { expected: vec![], found: vec![ValueType::I32] });
+	}
+
+	#[test]
+	fn block_missing_value_failure_binary() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     block (result i32)
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x07, 0x01, 0x05, 0x00, 0x02, 0x7f, 0x0b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.instruction_index, Some(1));
+		assert_eq!(error.error, InstructionError::ResultMismatch { expected: vec![ValueType::I32], found: vec![] });
+	}
+
+	#[test]
+	fn multi_value_function_unsupported() {
+		// WAST:
+		// (module
+		//   (func (result i32 i64)
+		//     i32.const 1
+		//     i64.const 2))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x00, 0x02, 0x7f, 0x7e,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x08, 0x01, 0x06, 0x00, 0x41, 0x01, 0x42, 0x02, 0x0b
+		];
+
+		// parity-wasm only models a single result, so the type section can't be read yet
+		match validate_bytes(&wasm, Filter::all()) {
+			Err(ValidationError::Deserialize(_)) => (),
+			_ => panic!("a function with two results can't be deserialized"),
+		}
+	}
+
+	#[test]
+	fn report_to_buffer() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1)
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     f32.neg))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0a, 0x02, 0x60, 0x00, 0x01, 0x7f, 0x60,
+			0x01, 0x7f, 0x01, 0x7f, 0x03, 0x03, 0x02, 0x00, 0x01, 0x0a, 0x0c, 0x02, 0x04, 0x00, 0x41, 0x01,
+			0x0b, 0x05, 0x00, 0x20, 0x00, 0x8c, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let mut buffer: Vec<u8> = vec![];
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert!(!validator.report_to(&mut buffer).unwrap());
+
+		let text = String::from_utf8(buffer).unwrap();
+		assert!(text.contains("function 0: ok"));
+		assert!(text.contains("function 1, instruction 1: invalid operation: expected"));
+	}
+
+	#[test]
+	fn load_alignment_failure_binary() {
+		// WAST:
+		// (module
+		//   (memory 1)
+		//   (func (result i64)
+		//     i32.const 0
+		//     i64.load align=16))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7e, 0x03,
+			0x02, 0x01, 0x00, 0x05, 0x03, 0x01, 0x00, 0x01, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x41, 0x00, 0x29,
+			0x04, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.instruction_index, Some(1));
+		assert_eq!(error.error, InstructionError::BadAlignment { align: 4, natural: 3 });
+	}
+
+	#[test]
+	fn store_natural_alignment_binary() {
+		// WAST:
+		// (module
+		//   (memory 1)
+		//   (func
+		//     i32.const 0
+		//     i64.const 1
+		//     i64.store8 align=1
+		//     i32.const 0
+		//     i32.const 1
+		//     i32.store align=4))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02,
+			0x01, 0x00, 0x05, 0x03, 0x01, 0x00, 0x01, 0x0a, 0x12, 0x01, 0x10, 0x00, 0x41, 0x00, 0x42, 0x01,
+			0x3c, 0x00, 0x00, 0x41, 0x00, 0x41, 0x01, 0x36, 0x02, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert!(validator.validate().unwrap());
+	}
+
+	#[test]
+	fn validate_twice() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1)
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     f32.neg))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0a, 0x02, 0x60, 0x00, 0x01, 0x7f, 0x60,
+			0x01, 0x7f, 0x01, 0x7f, 0x03, 0x03, 0x02, 0x00, 0x01, 0x0a, 0x0c, 0x02, 0x04, 0x00, 0x41, 0x01,
+			0x0b, 0x05, 0x00, 0x20, 0x00, 0x8c, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// nothing from one run is left over for the next, so both see the same error
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let first = validator.validate().unwrap_err();
+		let second = validator.validate().unwrap_err();
+		assert_eq!(first, second);
+		assert_eq!(first.function_index, Some(1));
+		assert!(validator.validate_function(0).unwrap());
+	}
+
+	#[test]
+	fn start_function_params_failure_binary() {
+		// WAST:
+		// (module
+		//   (func $start (param i32))
+		//   (start $start))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x01, 0x7f, 0x00, 0x03,
+			0x02, 0x01, 0x00, 0x08, 0x01, 0x00, 0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b, 0x00, 0x0f, 0x04, 0x6e,
+			0x61, 0x6d, 0x65, 0x01, 0x08, 0x01, 0x00, 0x05, 0x73, 0x74, 0x61, 0x72, 0x74
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert_eq!(validator.validate_module(), Err(InstructionError::InvalidStartFunction(0)));
+		assert_eq!(validator.validate().unwrap_err().error, InstructionError::InvalidStartFunction(0));
+	}
+
+	#[test]
+	fn export_missing_function_failure_binary() {
+		// WAST:
+		// (module
+		//   (import "env" "imported" (func))
+		//   (func)
+		//   (export "defined" (func 1))
+		//   (export "missing" (func 2)))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x02, 0x10,
+			0x01, 0x03, 0x65, 0x6e, 0x76, 0x08, 0x69, 0x6d, 0x70, 0x6f, 0x72, 0x74, 0x65, 0x64, 0x00, 0x00,
+			0x03, 0x02, 0x01, 0x00, 0x07, 0x15, 0x02, 0x07, 0x64, 0x65, 0x66, 0x69, 0x6e, 0x65, 0x64, 0x00,
+			0x01, 0x07, 0x6d, 0x69, 0x73, 0x73, 0x69, 0x6e, 0x67, 0x00, 0x02, 0x0a, 0x04, 0x01, 0x02, 0x00,
+			0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// the imported function takes index 0, so the defined one is 1 and nothing is at 2
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert_eq!(validator.validate_module(), Err(InstructionError::InvalidExport("missing".to_string())));
+	}
+
+	#[test]
+	fn module_error_in_every_entry_point_binary() {
+		// WAST:
+		// (module
+		//   (func)
+		//   (export "missing" (func 1)))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02,
+			0x01, 0x00, 0x07, 0x0b, 0x01, 0x07, 0x6d, 0x69, 0x73, 0x73, 0x69, 0x6e, 0x67, 0x00, 0x01, 0x0a,
+			0x04, 0x01, 0x02, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let expected = LocatedError::module(InstructionError::InvalidExport("missing".to_string()));
+		assert_eq!(validator.validate_all(), vec![expected.clone()]);
+
+		let report = validator.validate_report();
+		assert!(!report.valid);
+		assert!(report.per_function[0].valid);
+		assert_eq!(report.into_errors(), vec![expected]);
+
+		let mut buffer = Vec::new();
+		assert!(!validator.report_to(&mut buffer).unwrap());
+		let output = String::from_utf8(buffer).unwrap();
+		assert!(output.starts_with("module: "));
+		assert!(output.contains("function 0: ok"));
+	}
+
+	#[test]
+	fn global_init_type_failure_binary() {
+		// WAST:
+		// (module
+		//   (global (mut i32) (i32.const 0))
+		//   (global i32 (f32.const 1)))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x06, 0x0e, 0x02, 0x7f, 0x01, 0x41, 0x00, 0x0b,
+			0x7f, 0x00, 0x43, 0x00, 0x00, 0x80, 0x3f, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert_eq!(validator.validate_module(), Err(InstructionError::InvalidInitExpr(1)));
+	}
+
+	#[test]
+	fn data_segment_offset_failure_binary() {
+		// WAST:
+		// (module
+		//   (memory 1)
+		//   (data (i32.const 0) "ok")
+		//   (data (i64.const 8) "bad"))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x05, 0x03, 0x01, 0x00, 0x01, 0x0b, 0x10, 0x02,
+			0x00, 0x41, 0x00, 0x0b, 0x02, 0x6f, 0x6b, 0x00, 0x42, 0x08, 0x0b, 0x03, 0x62, 0x61, 0x64
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert_eq!(validator.validate_module(), Err(InstructionError::InvalidDataSegment(1)));
+	}
+
+	#[test]
+	fn element_segment_function_failure_binary() {
+		// WAST:
+		// (module
+		//   (table 2 anyfunc)
+		//   (func)
+		//   (elem (i32.const 0) 0 1))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02,
+			0x01, 0x00, 0x04, 0x04, 0x01, 0x70, 0x00, 0x02, 0x09, 0x08, 0x01, 0x00, 0x41, 0x00, 0x0b, 0x02,
+			0x00, 0x01, 0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// only function 0 exists, so the second element is dangling
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert_eq!(validator.validate_module(), Err(InstructionError::InvalidElementSegment(0)));
+	}
+
+	#[test]
+	fn unmatched_type_expected_found_binary() {
+		// WAST:
+		// (module
+		//   (func $addTwo (param f64 i32) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     i32.add)
+		//   (export "addTwo" (func $addTwo)))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7c, 0x7f, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x07, 0x0a, 0x01, 0x06, 0x61, 0x64, 0x64, 0x54, 0x77, 0x6f, 0x00,
+			0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b, 0x00, 0x10, 0x04, 0x6e,
+			0x61, 0x6d, 0x65, 0x01, 0x09, 0x01, 0x00, 0x06, 0x61, 0x64, 0x64, 0x54, 0x77, 0x6f
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// i32.add pops the i32 fine, then finds the f64 where its second operand should be
+		let validator = ModuleValidator::new(&module, Filter::NUMERIC);
+		match validator.validate().unwrap_err().error {
+			InstructionError::InvalidOperation { expected, found, .. } => {
+				assert_eq!(expected, ValueType::I32);
+				assert_eq!(found, ValueType::F64);
+			},
+			error => panic!("expected an invalid operation, found {:?}", error),
+		}
+	}
+
+	#[test]
+	fn validate_modules_batch() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1)
+		//   (func (result i32)
+		//     i64.const 1))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x03, 0x02, 0x00, 0x00, 0x0a, 0x0b, 0x02, 0x04, 0x00, 0x41, 0x01, 0x0b, 0x04, 0x00, 0x42, 0x01,
+			0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// the same bytes with the second function stripped make a valid module to go alongside the invalid one
+		let mut valid = module.clone();
+		valid.code_section_mut().unwrap().bodies_mut().truncate(1);
+		valid.function_section_mut().unwrap().entries_mut().truncate(1);
+
+		let results = validate_modules(&[valid, module], Filter::all());
+		assert_eq!(results.len(), 2);
+		assert!(results[0].as_ref().unwrap());
+		assert_eq!(results[1].as_ref().unwrap_err().function_index, Some(1));
+	}
+
+	#[test]
+	fn nop_instructions_binary() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     nop
+		//     i32.const 1
+		//     nop
+		//     nop
+		//     i32.const 2
+		//     i32.add
+		//     nop))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x0d, 0x01, 0x0b, 0x00, 0x01, 0x41, 0x01, 0x01, 0x01, 0x41, 0x02, 0x6a,
+			0x01, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// nop is known to the validator, so even a strict run accepts it
+		let validator = ModuleValidatorBuilder::new(&module).strict(true).build();
+		assert!(validator.validate().unwrap());
+		assert_eq!(validator.validate_report().instructions_checked, 8);
+	}
+
+	#[test]
+	#[cfg(feature = "simd")]
+	fn simd_strict_failure_binary() {
+		// The splat is encoded with the draft SIMD opcode parity-wasm reads, 0xfd 0x04
+		// WAST:
+		// (module
+		//   (func
+		//     i32.const 0
+		//     i8x16.splat
+		//     drop))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02,
+			0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x41, 0x00, 0xfd, 0x04, 0x1a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// SIMD isn't modeled, so it is only let through when validation isn't strict
+		assert!(ModuleValidator::new(&module, Filter::all()).validate().unwrap());
+		let validator = ModuleValidatorBuilder::new(&module).strict(true).build();
+		let error = validator.validate().unwrap_err();
+		assert_eq!((error.instruction_index, error.error.clone()), (Some(1), InstructionError::UnhandledInstruction("i8x16.splat".to_string())));
+		assert_eq!(error.error.to_string(), "i8x16.splat is not supported by the validator");
+	}
+
+	#[test]
+	fn unreachable_after_return_warning() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1
+		//     return
+		//     i32.const 2
+		//     drop
+		//     nop))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x0b, 0x01, 0x09, 0x00, 0x41, 0x01, 0x0f, 0x41, 0x02, 0x1a, 0x01, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// dead code is still valid, so it only shows up as a warning
+		let validator = ModuleValidatorBuilder::new(&module).warn_unreachable(true).build();
+		let report = validator.validate_report();
+		assert!(report.valid);
+		assert_eq!(report.per_function[0].unreachable, vec![2, 3, 4]);
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert!(validator.validate_report().per_function[0].unreachable.is_empty());
+	}
+
+	#[test]
+	fn many_locals_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     (local i32 i32 i32 i32 i32 i32 i32 i32 i64 i64 i64 i64 f32 f32 f64 f64)
+		//     i32.const 1))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x0e, 0x01, 0x0c, 0x04, 0x08, 0x7f, 0x04, 0x7e, 0x02, 0x7d, 0x02,
+			0x7c, 0x41, 0x01, 0x0b
+		];
+
+		let mut module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert!(validator.validate().unwrap());
+
+		// local counts are read straight from the binary, so they can add up past what a u32 index can reach
+		let body = &mut module.code_section_mut().unwrap().bodies_mut()[0];
+		*body.locals_mut() = vec![Local::new(u32::MAX, ValueType::I32)];
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert_eq!(validator.validate().unwrap_err().error, InstructionError::TooManyLocals);
+	}
+
+	#[test]
+	fn thousands_of_local_accesses() {
+		let mut instructions = vec![Instruction::GetLocal(0)];
+		for _ in 0..1000 {
+			instructions.push(Instruction::GetLocal(1));
+			instructions.push(Instruction::I64Eqz);
+			instructions.push(Instruction::I32Add);
+			instructions.push(Instruction::GetLocal(0));
+			instructions.push(Instruction::I32Add);
+		}
+		instructions.push(Instruction::End);
+		let function_type = FunctionType::new(vec![ValueType::I32, ValueType::I64], Some(ValueType::I32));
+		let module_with = |instructions: Vec<Instruction>| Module::new(vec![
+			Section::Type(TypeSection::with_types(vec![Type::Function(function_type.clone())])),
+			Section::Function(FunctionSection::with_entries(vec![Func::new(0)])),
+			Section::Code(CodeSection::with_bodies(vec![FuncBody::new(vec![], Instructions::new(instructions))])),
+		]);
+
+		let module = module_with(instructions.clone());
+		assert!(ModuleValidator::new(&module, Filter::all()).validate().unwrap());
+
+		// the locals are only worked out once per function, so a late access has to see the same types as the first
+		instructions[4001] = Instruction::GetLocal(0);
+		let module = module_with(instructions);
+		let error = ModuleValidator::new(&module, Filter::all()).validate().unwrap_err();
+		assert_eq!(error.instruction_index, Some(4002));
+		assert_eq!(error.error, InstructionError::InvalidOperation { expected: ValueType::I64, found: ValueType::I32 });
+	}
+
+	#[test]
+	fn errors_iterator() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i64.const 1)
+		//   (func (result i32)
+		//     i32.const 1)
+		//   (func (result f32)
+		//     i32.const 1
+		//     f32.neg))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x09, 0x02, 0x60, 0x00, 0x01, 0x7f, 0x60,
+			0x00, 0x01, 0x7d, 0x03, 0x04, 0x03, 0x00, 0x00, 0x01, 0x0a, 0x11, 0x03, 0x04, 0x00, 0x42, 0x01,
+			0x0b, 0x04, 0x00, 0x41, 0x01, 0x0b, 0x05, 0x00, 0x41, 0x01, 0x8c, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let mut errors = validator.errors();
+		assert_eq!(errors.next().unwrap().function_index, Some(0));
+		assert_eq!(errors.count(), 1);
+		assert_eq!(validator.errors().filter(|error| error.function_index == Some(2)).count(), 1);
+	}
+
+	#[test]
+	fn rule_rejects_f64_add() {
+		// WAST:
+		// (module
+		//   (func (param f64 f64) (result f64)
+		//     get_local 0
+		//     get_local 1
+		//     f64.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7c, 0x7c, 0x01,
+			0x7c, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0xa0, 0x0b
+		];
+
+		struct NoF64Add;
+
+		impl InstructionRule for NoF64Add {
+			fn check(&self, instruction: &Instruction, stack: &[ValueType]) -> Result<(), InstructionError> {
+				match instruction {
+					Instruction::F64Add => {
+						assert_eq!(stack, &[ValueType::F64, ValueType::F64][..]);
+						Err(InstructionError::ForbiddenInstruction(instruction.to_string()))
+					},
+					_ => Ok(()),
+				}
+			}
+		}
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let mut validator = ModuleValidator::new(&module, Filter::all());
+		assert!(validator.validate().unwrap());
+		validator.add_rule(Box::new(NoF64Add));
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.instruction_index, Some(2));
+		assert_eq!(error.error, InstructionError::ForbiddenInstruction("f64.add".to_string()));
+	}
+
+	#[test]
+	fn no_float_rule() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1
+		//     i32.const 2
+		//     i32.add)
+		//   (func (param f32 f32) (result f32)
+		//     get_local 0
+		//     get_local 1
+		//     f32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0b, 0x02, 0x60, 0x00, 0x01, 0x7f, 0x60,
+			0x02, 0x7d, 0x7d, 0x01, 0x7d, 0x03, 0x03, 0x02, 0x00, 0x01, 0x0a, 0x11, 0x02, 0x07, 0x00, 0x41,
+			0x01, 0x41, 0x02, 0x6a, 0x0b, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x92, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let mut validator = ModuleValidator::new(&module, Filter::all());
+		validator.add_rule(Box::new(NoFloat));
+		assert!(validator.validate_function(0).unwrap());
+		let errors = validator.validate_all();
+		let forbidden: Vec<_> = errors.into_iter().map(|error| (error.function_index, error.error)).collect();
+		// the float parameters are already forbidden where they are read
+		assert_eq!(forbidden, vec![
+			(Some(1), InstructionError::ForbiddenInstruction("get_local 0".to_string())),
+			(Some(1), InstructionError::ForbiddenInstruction("get_local 1".to_string())),
+			(Some(1), InstructionError::ForbiddenInstruction("f32.add".to_string())),
+		]);
+	}
+
+	#[test]
+	fn memory_maximum_below_minimum_failure_binary() {
+		// WAST:
+		// (module
+		//   (memory 2 1))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x05, 0x04, 0x01, 0x01, 0x02, 0x01
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert_eq!(validator.validate_module(), Err(InstructionError::InvalidLimits));
+	}
+
+	#[test]
+	fn memory_too_many_pages_failure_binary() {
+		// WAST:
+		// (module
+		//   (memory 65537))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x05, 0x05, 0x01, 0x00, 0x81, 0x80, 0x04
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert_eq!(validator.validate_module(), Err(InstructionError::InvalidLimits));
+	}
+
+	#[test]
+	fn mvp_strict_two_memories_failure_binary() {
+		// WAST:
+		// (module
+		//   (memory 1)
+		//   (memory 1))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x05, 0x05, 0x02, 0x00, 0x01, 0x00, 0x01
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		assert_eq!(ModuleValidator::new(&module, Filter::all()).validate_module(), Ok(()));
+		let validator = ModuleValidatorBuilder::new(&module).mvp_strict(true).build();
+		assert_eq!(validator.validate_module(), Err(InstructionError::TooManyMemories));
+	}
+
+	#[test]
+	fn type_index_block_unsupported() {
+		// WAST:
+		// (module
+		//   (type (func (param i32) (result i64)))
+		//   (func (result i64)
+		//     i32.const 1
+		//     block (type 0)
+		//       i64.extend_u/i32
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0a, 0x02, 0x60, 0x01, 0x7f, 0x01, 0x7e,
+			0x60, 0x00, 0x01, 0x7e, 0x03, 0x02, 0x01, 0x01, 0x0a, 0x0a, 0x01, 0x08, 0x00, 0x41, 0x01, 0x02,
+			0x00, 0xad, 0x0b, 0x0b
+		];
+
+		// parity-wasm only reads empty and single-value block types, so the code section can't be read yet
+		match validate_bytes(&wasm, Filter::all()) {
+			Err(ValidationError::Deserialize(_)) => (),
+			_ => panic!("a block with a type index can't be deserialized"),
+		}
+	}
+
+	#[test]
+	fn instruction_limit_exceeded() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1
+		//     i32.const 2
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x41, 0x01, 0x41, 0x02, 0x6a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// the closing end counts as an instruction too
+		let validator = ModuleValidatorBuilder::new(&module).max_instructions_per_function(4).build();
+		assert!(validator.validate().unwrap());
+		let validator = ModuleValidatorBuilder::new(&module).max_instructions_per_function(3).build();
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.error, InstructionError::LimitExceeded(Limit::Instructions));
+		assert_eq!(error.to_string(), "function 0, instruction 0: too many instructions");
+	}
+
+	#[test]
+	fn br_table_target_mismatch_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     block (result i32)
+		//       block
+		//         i32.const 7
+		//         get_local 0
+		//         br_table 1 0 1
+		//       end
+		//       i32.const 0
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x15, 0x01, 0x13, 0x00, 0x02, 0x7f, 0x02, 0x40, 0x41, 0x07, 0x20,
+			0x00, 0x0e, 0x02, 0x01, 0x00, 0x01, 0x0b, 0x41, 0x00, 0x0b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// the default target is the outer block, which takes an i32, while the inner one at depth 0 takes nothing
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.instruction_index, Some(4));
+		assert_eq!(error.error, InstructionError::BranchTargetMismatch {
+			expected: vec![ValueType::I32],
+			got: vec![],
+			target: 0,
+		});
+	}
+
+	/// Checks that a narrow load still pops an i32 address and pushes the full width of its result type
+	fn assert_narrow_load(instruction: Instruction, pushed: ValueType) {
+		let signature = get_load_signature(&instruction).unwrap();
+		assert_eq!(signature.pop, vec![ValueType::I32]);
+		assert_eq!(signature.push, vec![pushed]);
+	}
+
+	#[test]
+	fn i32_load8_s_signature() {
+		assert_narrow_load(Instruction::I32Load8S(0, 0), ValueType::I32);
+	}
+
+	#[test]
+	fn i32_load8_u_signature() {
+		assert_narrow_load(Instruction::I32Load8U(0, 0), ValueType::I32);
+	}
+
+	#[test]
+	fn i32_load16_s_signature() {
+		assert_narrow_load(Instruction::I32Load16S(0, 0), ValueType::I32);
+	}
+
+	#[test]
+	fn i32_load16_u_signature() {
+		assert_narrow_load(Instruction::I32Load16U(0, 0), ValueType::I32);
+	}
+
+	#[test]
+	fn i64_load8_s_signature() {
+		assert_narrow_load(Instruction::I64Load8S(0, 0), ValueType::I64);
+	}
+
+	#[test]
+	fn i64_load8_u_signature() {
+		assert_narrow_load(Instruction::I64Load8U(0, 0), ValueType::I64);
+	}
+
+	#[test]
+	fn i64_load16_s_signature() {
+		assert_narrow_load(Instruction::I64Load16S(0, 0), ValueType::I64);
+	}
+
+	#[test]
+	fn i64_load16_u_signature() {
+		assert_narrow_load(Instruction::I64Load16U(0, 0), ValueType::I64);
+	}
+
+	#[test]
+	fn i64_load32_s_signature() {
+		assert_narrow_load(Instruction::I64Load32S(0, 0), ValueType::I64);
+	}
+
+	#[test]
+	fn i64_load32_u_signature() {
+		assert_narrow_load(Instruction::I64Load32U(0, 0), ValueType::I64);
+	}
+
+	#[test]
+	fn void_function_leftover_failure_binary() {
+		// WAST:
+		// (module
+		//   (func
+		//     i32.const 1))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02,
+			0x01, 0x00, 0x0a, 0x06, 0x01, 0x04, 0x00, 0x41, 0x01, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.error, InstructionError::NonEmptyStack { leftover: vec![ValueType::I32] });
+	}
+
+	#[test]
+	fn value_type_names_round_trip() {
+		for value_type in [ValueType::I32, ValueType::I64, ValueType::F32, ValueType::F64].iter() {
+			assert_eq!(value_type_from_name(value_type_name(*value_type)), Some(*value_type));
+		}
+		assert_eq!(value_type_name(ValueType::F64), "f64");
+		assert_eq!(value_type_from_name("i128"), None);
+	}
+
+	#[test]
+	fn param_before_local_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result f64)
+		//     (local f64)
+		//     get_local 0
+		//     drop
+		//     get_local 1))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7c,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x0b, 0x01, 0x09, 0x01, 0x01, 0x7c, 0x20, 0x00, 0x1a, 0x20, 0x01,
+			0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// the parameter is local 0 and the declared local is local 1, not the other way around
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert!(validator.validate().unwrap());
+	}
+
+	#[test]
+	#[cfg(feature = "sign_ext")]
+	fn sign_ext_disabled_under_mvp() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     get_local 0
+		//     i32.extend8_s))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x07, 0x01, 0x05, 0x00, 0x20, 0x00, 0xc0, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		assert!(ModuleValidator::new(&module, Filter::all()).validate().unwrap());
+		let validator = ModuleValidatorBuilder::new(&module).features(FeatureSet::mvp()).build();
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.instruction_index, Some(1));
+		assert_eq!(error.error, InstructionError::FeatureDisabled(Feature::SignExt));
+	}
+
+	#[test]
+	fn report_into_errors() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i64.const 1)
+		//   (func (result i32)
+		//     i32.const 1)
+		//   (func (result f32)
+		//     i32.const 1
+		//     f32.neg))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x09, 0x02, 0x60, 0x00, 0x01, 0x7f, 0x60,
+			0x00, 0x01, 0x7d, 0x03, 0x04, 0x03, 0x00, 0x00, 0x01, 0x0a, 0x11, 0x03, 0x04, 0x00, 0x42, 0x01,
+			0x0b, 0x04, 0x00, 0x41, 0x01, 0x0b, 0x05, 0x00, 0x41, 0x01, 0x8c, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let errors = validator.validate_report().into_errors();
+		assert_eq!(errors.len(), 2);
+		assert_eq!(errors[0].function_index, Some(0));
+		assert_eq!(errors[1].function_index, Some(2));
+	}
+
+	#[test]
+	fn drop_past_block_failure_binary() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1
+		//     block
+		//       drop
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x0a, 0x01, 0x08, 0x00, 0x41, 0x01, 0x02, 0x40, 0x1a, 0x0b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// the i32 belongs to the function body, so the block can't drop it
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let error = validator.validate().unwrap_err();
+		assert_eq!((error.instruction_index, error.error), (Some(2), InstructionError::StackUnderflow));
+	}
+
+	#[test]
+	fn byte_offset_failure_binary() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1
+		//     i64.const 2
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x41, 0x01, 0x42, 0x02, 0x6a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.byte_offset, Some(28));
+		assert_eq!(wasm[28], 0x6a);
 	}
 
-	/// Evaluates a signature and determines if the stack can support the instruction in it's current state
-	fn validate_instruction(&mut self, signature: &Signature, instruction: &Instruction) -> Result<bool, InstructionError> {
-		for signature_value in &signature.pop {
-			let value = self.stack.pop();
-			match value {
-				Some(stack_value) => {
-					if stack_value != *signature_value {
-						return Err(InstructionError::InvalidOperation(instruction.clone()))
-					}
-				}
-				None => return Err(InstructionError::InvalidOperation(instruction.clone())) // Instructions are small, so clone
+	#[test]
+	fn byte_offset_padded_leb_binary() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1
+		//     i64.const 2
+		//     i32.add))
+		// with the code section's size padded to five bytes
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x89, 0x80, 0x80, 0x80, 0x00, 0x01, 0x07, 0x00, 0x41, 0x01, 0x42, 0x02,
+			0x6a, 0x0b
+		];
 
-			}
-		}
-		self.stack.extend(&signature.push);
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
 
-		Ok(true)
-	}
+		let validator = ModuleValidatorBuilder::new(&module).source(&wasm).build();
+		let error = validator.validate().unwrap_err();
+		assert_eq!(error.byte_offset, Some(32));
+		assert_eq!(wasm[32], 0x6a);
 
-	/// A method used to load global or local variable types onto the stack
-	fn push_global_or_local(&mut self, instruction: &Instruction, body: &FuncBody, index: usize) -> Result<bool, InstructionError> {
+		// without the source, the offset is into the module as parity-wasm would write it
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert_eq!(validator.validate().unwrap_err().byte_offset, Some(28));
+	}
 
-		// These next couple lines are just to get the parameters of the function we're dealing with.
-		// We need the parameters because they can be loaded like local variables but they're not in the locals vec
+	#[test]
+	fn untrusted_truncated_and_random_bytes() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1
+		//     i64.const 2
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x41, 0x01, 0x42, 0x02, 0x6a, 0x0b
+		];
 
-		// type_ref is the index of the FunctionType in types_section
-		let type_ref = self.module.function_section().unwrap().entries()[index].type_ref();
-		let type_variant = &self.module.type_section().unwrap().types()[type_ref as usize];
+		assert!(validate_untrusted(&wasm).is_err());
+		// cutting a section short leaves the module unreadable
+		for len in (0..8).chain(9..15).chain(16..19).chain(20..wasm.len()) {
+			assert!(validate_untrusted(&wasm[..len]).is_err(), "prefix of {} bytes", len);
+		}
 
-		let mut locals = body.locals().to_vec();
-		match type_variant {
-			Type::Function(ftype) => {
-				locals.extend(ftype.params().iter().map(|f| Local::new(0, *f)));
-			}
+		let mut seed: u32 = 0x2545_f491;
+		for len in 0..64 {
+			let garbage: Vec<u8> = (0..len).map(|_| {
+				seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+				(seed >> 16) as u8
+			}).collect();
+			assert!(validate_untrusted(&garbage).is_err());
+			// past the header, so the sections themselves are read
+			let sections: Vec<u8> = wasm[..8].iter().chain(&garbage).copied().collect();
+			let _ = validate_untrusted(&sections);
 		}
 
-		match instruction {
-			Instruction::GetGlobal(local) => {
-				match locals.get(*local as usize) {
-					Some(variable) => {
-						self.stack.push(variable.value_type());
-						Ok(true)
-					},
-					None => { Err(InstructionError::GlobalNotFound) },
-				}
-			},
-			Instruction::GetLocal(local) => {
-				match locals.get(*local as usize) {
-					Some(variable) => {
-						self.stack.push(variable.value_type());
-						Ok(true)
-					},
-					None => { Err(InstructionError::LocalNotFound) },
-				}
-			},
-			_ => { Err(InstructionError::UnmatchedInstruction) },
+		// lengths and counts of 2^32 - 1 with only a few bytes behind them
+		let header = &wasm[..8];
+		let huge = [0xff, 0xff, 0xff, 0xff, 0x0f];
+		let declarations: [&[u8]; 5] = [
+			// a type section
+			&[0x01],
+			// a custom section
+			&[0x00],
+			// the entries of a type section
+			&[0x01, 0x06, 0xff, 0xff, 0xff, 0xff, 0x0f],
+			// the name of an imported module
+			&[0x02, 0x07, 0x01, 0xff, 0xff, 0xff, 0xff, 0x0f],
+			// a function body
+			&[0x0a, 0x07, 0x01, 0xff, 0xff, 0xff, 0xff, 0x0f],
+		];
+		for declaration in declarations.iter() {
+			let bytes: Vec<u8> = header.iter().chain(declaration.iter()).chain(&huge).copied().collect();
+			assert!(validate_untrusted(&bytes).is_err(), "{:x?}", declaration);
 		}
-	}
-}
 
-/// Checks the **discriminant** of an instruction against the **discriminants** of a container,
-/// to determine if the given instruction is in the container while ignoring values.
-fn contains(instruction: &Instruction, container: &[Instruction]) -> bool {
-	container.iter().any(|f| discriminant(f) == discriminant(instruction))
-}
+		// an invalid function followed by a `name` section claiming 2^32 - 1 function names
+		let names = [
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b, 0x00, 0x0c, 0x04, 0x6e, 0x61, 0x6d, 0x65,
+			0x01, 0x05, 0xff, 0xff, 0xff, 0xff, 0x0f
+		];
+		assert!(validate_untrusted(&names).is_err());
+	}
 
-/// Given an instruction, determine it's signature based on what classification it is in.
-fn get_instruction_signature(instruction: &Instruction) -> Option<Signature> {
-	// returns some signature if there is a type we are interested in
-	// returns None otherwise
-	if contains(instruction, &I32_BINOP) {
-		Some(Signature{ pop: [ValueType::I32; 2].to_vec(), push: [ValueType::I32; 1].to_vec() })
-	} else if contains(instruction, &I64_BINOP) {
-		Some(Signature{ pop: [ValueType::I64; 2].to_vec(), push: [ValueType::I64; 1].to_vec() })
-	} else if contains(instruction, &F32_BINOP) {
-		Some(Signature{ pop: [ValueType::F32; 2].to_vec(), push: [ValueType::F32; 1].to_vec() })
-	} else if contains(instruction, &F64_BINOP) {
-		Some(Signature{ pop: [ValueType::F64; 2].to_vec(), push: [ValueType::F64; 1].to_vec() })
-	} else if contains(instruction, &CONST_INST) {
-		get_const_signature(instruction)
-	} else {
-		None
-	}
-}
-
-/// Determines the signature of a const instruction, which are slightly different from regular instructions	
-fn get_const_signature(instruction: &Instruction) -> Option<Signature> {
-	let inst_type = &format!("{:?}", instruction)[..3];
+	#[test]
+	fn complex_instruction_binary_category_counts() {
+		let module = deserialize_buffer::<Module>(COMPLEX_INSTRUCTION_WASM).unwrap();
 
-	match inst_type {
-		"I32" => Some(Signature{ pop: [].to_vec(), push: [ValueType::I32; 1].to_vec() }),
-		"I64" => Some(Signature{ pop: [].to_vec(), push: [ValueType::I64; 1].to_vec() }),
-		"F32" => Some(Signature{ pop: [].to_vec(), push: [ValueType::F32; 1].to_vec() }),
-		"F64" => Some(Signature{ pop: [].to_vec(), push: [ValueType::F64; 1].to_vec() }),
-		_ => None
+		let report = ModuleValidator::new(&module, Filter::all()).validate_report();
+		assert_eq!(report.category_counts.get("I32_BINOP"), Some(&3));
+		assert_eq!(report.category_counts.get("GET_INST"), Some(&6));
+		assert_eq!(report.category_counts.get("CONTROL_INST"), Some(&3));
+		assert_eq!(report.category_counts.get("CONST_INST"), None);
 	}
-}
 
+	#[test]
+	fn params_without_body_locals_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32 i64 f32 f64) (result f64)
+		//     local.get 0
+		//     drop
+		//     local.get 1
+		//     drop
+		//     local.get 2
+		//     drop
+		//     local.get 3))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x09, 0x01, 0x60, 0x04, 0x7f, 0x7e, 0x7d,
+			0x7c, 0x01, 0x7c, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x0f, 0x01, 0x0d, 0x00, 0x20, 0x00, 0x1a, 0x20,
+			0x01, 0x1a, 0x20, 0x02, 0x1a, 0x20, 0x03, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
 
-#[cfg(test)]
-mod tests {
-	use super::*;
-	use parity_wasm::elements::deserialize_buffer;
-	use parity_wasm::deserialize_file;
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let is_valid = validator.validate().unwrap();
+		assert!(is_valid)
+	}
 
 	#[test]
-	fn simple_instructions_binary() {
+	fn grouped_body_locals_binary() {
 		// WAST:
 		// (module
-		//   (type $t0 (func (param i32 i32) (result i32)))
-		//   (func $f0 (type $t0) (param $p0 i32) (param $p1 i32) (result i32)
-		//     (i32.add
-		//       (get_local $p0)
-		//       (get_local $p1))))
+		//   (func (param i32) (result i64)
+		//     (local f64 f64 i64)
+		//     local.get 2
+		//     local.set 1
+		//     local.get 3))
 		let wasm: Vec<u8> = vec![
-			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7f, 0x01,
-			0x7f, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b,
-			0x00, 0x14, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x02, 0x0d, 0x01, 0x00, 0x02, 0x00, 0x03, 0x6c, 0x68,
-			0x73, 0x01, 0x03, 0x72, 0x68, 0x73
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7e,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x0e, 0x01, 0x0c, 0x02, 0x02, 0x7c, 0x01, 0x7e, 0x20, 0x02, 0x21,
+			0x01, 0x20, 0x03, 0x0b
 		];
 
 		let module = deserialize_buffer::<Module>(&wasm).unwrap();
 
-		let mut validator = ModuleValidator::new(&module, NumericInstructions);
+		let validator = ModuleValidator::new(&module, Filter::all());
 		let is_valid = validator.validate().unwrap();
 		assert!(is_valid)
 	}
 
 	#[test]
-	fn const_instructions_binary() {
+	fn owned_validator_binary() {
+		// WAST:
+		// (module
+		//   (func (param i32) (result i32)
+		//     local.get 0
+		//     i32.const 1
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x41, 0x01, 0x6a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		struct Holder {
+			validator: OwnedModuleValidator,
+		}
+		let holder = Holder { validator: OwnedModuleValidator::new(module, Filter::all()) };
+		assert!(holder.validator.validate().unwrap());
+		assert!(holder.validator.validator().validate_all().is_empty());
+		assert_eq!(holder.validator.into_module().code_section().unwrap().bodies().len(), 1);
+
+		// the builder's settings carry over, and what errors read from the module is kept between validators
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+		let owned = ModuleValidatorBuilder::new(&module).max_stack_height(1).source(&wasm).build_owned();
+		let error = owned.validate().unwrap_err();
+		assert_eq!((error.error, error.byte_offset), (InstructionError::StackOverflow(1), Some(27)));
+		assert!(owned.lazy.offsets.get().is_some());
+		assert_eq!(owned.validator().validate_all().len(), 1);
+	}
+	#[test]
+	fn section_length_mismatch_failure() {
+		let body = || FuncBody::new(vec![], Instructions::new(vec![Instruction::End]));
+		let module = Module::new(vec![
+			Section::Type(TypeSection::with_types(vec![Type::Function(FunctionType::new(vec![], None))])),
+			Section::Function(FunctionSection::with_entries(vec![Func::new(0)])),
+			Section::Code(CodeSection::with_bodies(vec![body(), body()])),
+		]);
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert_eq!(validator.validate_module(), Err(InstructionError::SectionLengthMismatch { functions: 1, bodies: 2 }));
+		assert_eq!(validator.validate().unwrap_err().error, InstructionError::SectionLengthMismatch { functions: 1, bodies: 2 });
+		// the extra body has no type to check against, which is reported rather than indexed past
+		assert_eq!(validator.validate_function(1), Err(InstructionError::MalformedModule));
+	}
+	#[test]
+	fn const_instructions_classify() {
 		// WAST:
 		// (module
 		//   (table 0 anyfunc)
@@ -255,24 +5399,125 @@ mod tests {
 
 		let module = deserialize_buffer::<Module>(&wasm).unwrap();
 
-		let mut validator = ModuleValidator::new(&module, NumericInstructions);
-		let is_valid = validator.validate().unwrap();
-		assert!(is_valid)
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let categories: HashSet<Category> = [Category::Const, Category::Control].iter().copied().collect();
+		assert_eq!(validator.classify(), categories);
 	}
 
 	#[test]
-	#[should_panic]
-	fn unmatched_type_failure_binary() {
-		// Binary incorrectly tries to add an f64 with i32 to return an i32
-		// This should be failed by the validator
+	fn conversion_br_table_classify_binary() {
 		// WAST:
 		// (module
-		//   (func $addTwo (param f64 i32) (result i32)
+		//   (func
+		//     block
+		//       f32.const 1
+		//       i32.trunc_s/f32
+		//       br_table 0 0
+		//     end))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03, 0x02,
+			0x01, 0x00, 0x0a, 0x11, 0x01, 0x0f, 0x00, 0x02, 0x40, 0x43, 0x00, 0x00, 0x80, 0x3f, 0xa8, 0x0e,
+			0x01, 0x00, 0x00, 0x0b, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let categories: HashSet<Category> = [Category::Const, Category::Conversion, Category::Control].iter().copied().collect();
+		assert_eq!(validator.classify(), categories);
+	}
+
+	#[test]
+	fn classify_instruction_kinds() {
+		let kinds = [
+			(Instruction::GetLocal(3), Category::Get),
+			(Instruction::BrIf(1), Category::Control),
+			(Instruction::CallIndirect(2, 0), Category::Call),
+			(Instruction::Select, Category::Parametric),
+			(Instruction::TeeLocal(1), Category::Set),
+			(Instruction::F64Const(0), Category::Const),
+			(Instruction::I32Eqz, Category::I32Testop),
+			(Instruction::I64Eqz, Category::I64Testop),
+			(Instruction::I64Load32U(2, 8), Category::Load),
+			(Instruction::F32Store(2, 0), Category::Store),
+			(Instruction::GrowMemory(0), Category::Memory),
+			(Instruction::I32Popcnt, Category::I32Unop),
+			(Instruction::I64Ctz, Category::I64Unop),
+			(Instruction::I32Rotl, Category::I32Binop),
+			(Instruction::I32GeU, Category::I32Relop),
+			(Instruction::I64Xor, Category::I64Binop),
+			(Instruction::I64LtS, Category::I64Relop),
+			(Instruction::F32Sqrt, Category::F32Unop),
+			(Instruction::F64Nearest, Category::F64Unop),
+			(Instruction::F32Copysign, Category::F32Binop),
+			(Instruction::F64Max, Category::F64Binop),
+			(Instruction::F32Ne, Category::F32Relop),
+			(Instruction::F64Le, Category::F64Relop),
+			(Instruction::I32WrapI64, Category::Conversion),
+			(Instruction::F64ReinterpretI64, Category::Conversion),
+			(Instruction::BrTable(Box::new(BrTableData { table: Box::new([0, 1]), default: 2 })), Category::Control),
+		];
+		for (instruction, category) in kinds.iter() {
+			assert_eq!(classify_instruction(instruction), Some(*category), "{:?}", instruction);
+		}
+		// the category exists whatever features the crate is built with
+		assert_eq!(Category::ExtendSign.name(), "EXTEND_SIGN");
+	}
+
+	#[test]
+	fn import_missing_type_failure() {
+		let import = |field: &str, type_ref| ImportEntry::new("env".to_string(), field.to_string(), External::Function(type_ref));
+		let module = Module::new(vec![
+			Section::Type(TypeSection::with_types(vec![Type::Function(FunctionType::new(vec![], None))])),
+			Section::Import(ImportSection::with_entries(vec![import("f", 0), import("g", 1)])),
+		]);
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert_eq!(validator.validate_module(), Err(InstructionError::InvalidImport { index: 1 }));
+	}
+
+	#[test]
+	fn reference_types_unsupported_binary() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     ref.null func
+		//     ref.is_null))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x07, 0x01, 0x05, 0x00, 0xd0, 0x70, 0xd1, 0x0b
+		];
+
+		// parity-wasm can't read reference-typed instructions, so the module is rejected before it reaches the validator
+		assert!(deserialize_buffer::<Module>(&wasm).is_err());
+		match validate_untrusted(&wasm) {
+			Err(ValidationError::Deserialize(_)) => (),
+			_ => panic!("ref.null and ref.is_null should fail to deserialize"),
+		}
+	}
+	#[test]
+	fn is_valid_binary() {
+		// WAST:
+		// (module
+		//   (func $addTwo (param i32 i32) (result i32)
 		//     get_local 0
 		//     get_local 1
 		//     i32.add)
 		//   (export "addTwo" (func $addTwo)))
 		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7f, 0x01,
+			0x7f, 0x03, 0x02, 0x01, 0x00, 0x07, 0x0a, 0x01, 0x06, 0x61, 0x64, 0x64, 0x54, 0x77, 0x6f, 0x00,
+			0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b, 0x00, 0x10, 0x04, 0x6e,
+			0x61, 0x6d, 0x65, 0x01, 0x09, 0x01, 0x00, 0x06, 0x61, 0x64, 0x64, 0x54, 0x77, 0x6f
+		];
+		// WAST:
+		// (module
+		//   (func $addTwo (param f64 i32) (result i32)
+		//     get_local 0
+		//     get_local 1
+		//     i32.add)
+		//   (export "addTwo" (func $addTwo)))
+		let unmatched: Vec<u8> = vec![
 			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7c, 0x7f, 0x01,
 			0x7f, 0x03, 0x02, 0x01, 0x00, 0x07, 0x0a, 0x01, 0x06, 0x61, 0x64, 0x64, 0x54, 0x77, 0x6f, 0x00,
 			0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b, 0x00, 0x19, 0x04, 0x6e,
@@ -281,65 +5526,336 @@ mod tests {
 		];
 
 		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+		assert!(is_valid(&module, Filter::all()));
 
-		let mut validator = ModuleValidator::new(&module, NumericInstructions);
-		validator.validate().unwrap();
+		let module = deserialize_buffer::<Module>(&unmatched).unwrap();
+		assert!(!is_valid(&module, Filter::all()));
+	}
+	#[test]
+	fn function_end_closes_body() {
+		let module = |instructions: Vec<Instruction>| Module::new(vec![
+			Section::Type(TypeSection::with_types(vec![Type::Function(FunctionType::new(vec![], Some(ValueType::I32)))])),
+			Section::Function(FunctionSection::with_entries(vec![Func::new(0)])),
+			Section::Code(CodeSection::with_bodies(vec![FuncBody::new(vec![], Instructions::new(instructions))])),
+		]);
+
+		let valid = module(vec![Instruction::I32Const(1), Instruction::End]);
+		assert!(ModuleValidator::new(&valid, Filter::all()).validate().unwrap());
+
+		// the result is checked at the body's own end
+		let wrong_result = module(vec![Instruction::I64Const(1), Instruction::End]);
+		let error = ModuleValidator::new(&wrong_result, Filter::all()).validate().unwrap_err();
+		assert_eq!((error.instruction_index, error.error), (Some(1), InstructionError::ResultMismatch { expected: vec![ValueType::I32], found: vec![ValueType::I64] }));
+
+		// once the body is closed there is no block left for another end to close
+		let extra_end = module(vec![Instruction::I32Const(1), Instruction::End, Instruction::End]);
+		let error = ModuleValidator::new(&extra_end, Filter::all()).validate().unwrap_err();
+		assert_eq!((error.instruction_index, error.error), (Some(2), InstructionError::UnmatchedInstruction));
 	}
 
 	#[test]
-	fn simple_wasm_from_file() {
+	fn warnings_as_errors_binary() {
 		// WAST:
 		// (module
-		//   (func $addTwo (param f64 i32) (result i32)
+		//   (func (result i32)
+		//     i32.const 1
+		//     return
+		//     i32.const 2
+		//     drop
+		//     nop))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x0b, 0x01, 0x09, 0x00, 0x41, 0x01, 0x0f, 0x41, 0x02, 0x1a, 0x01, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		assert!(ModuleValidator::new(&module, Filter::all()).validate().unwrap());
+
+		let validator = ModuleValidatorBuilder::new(&module).warnings_as_errors(true).build();
+		let error = validator.validate().unwrap_err();
+		assert_eq!((error.function_index, error.instruction_index, error.error.clone()), (Some(0), Some(2), InstructionError::UnreachableCode));
+		// the single pass of `validate` agrees with the warnings the report lists
+		let report = validator.validate_report();
+		assert_eq!(report.per_function[0].unreachable, vec![2, 3, 4]);
+
+		// and every other entry point fails the module the same way
+		assert!(!report.valid);
+		assert_eq!(report.into_errors(), vec![error.clone()]);
+		assert_eq!(validator.validate_all(), vec![error]);
+		let mut buffer = Vec::new();
+		assert!(!validator.report_to(&mut buffer).unwrap());
+		let output = String::from_utf8(buffer).unwrap();
+		assert!(output.starts_with("function 0, instruction 2: unreachable code\n"));
+
+		// without the flag, the report only warns
+		let validator = ModuleValidatorBuilder::new(&module).warn_unreachable(true).build();
+		assert!(validator.validate_report().valid);
+	}
+
+	#[test]
+	fn call_indirect_funcref_table_binary() {
+		// WAST:
+		// (module
+		//   (type $t (func (result i32)))
+		//   (table 1 funcref)
+		//   (func (result i32)
+		//     i32.const 0
+		//     call_indirect (type $t)))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x04, 0x04, 0x01, 0x70, 0x00, 0x01, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x41, 0x00,
+			0x11, 0x00, 0x00, 0x0b, 0x00, 0x0b, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x04, 0x04, 0x01, 0x00, 0x01,
+			0x74
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// parity-wasm only reads tables of functions, so a table of anything else can't be built to test InvalidTableType
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert!(validator.validate().unwrap());
+	}
+
+	#[test]
+	fn default_filter_binary() {
+		// WAST:
+		// (module
+		//   (memory 1)
+		//   (func (result i32)
+		//     f32.const 0
+		//     i32.load))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x05, 0x03, 0x01, 0x00, 0x01, 0x0a, 0x0c, 0x01, 0x0a, 0x00, 0x43, 0x00, 0x00,
+			0x00, 0x00, 0x28, 0x02, 0x00, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		assert_eq!(Filter::default(), Filter::all());
+		assert_eq!(Filter::numeric(), Filter::NUMERIC);
+
+		// the load's address is only checked when memory instructions are
+		assert!(ModuleValidator::new(&module, Filter::numeric()).validate().unwrap());
+		let error = ModuleValidator::new(&module, Filter::default()).validate().unwrap_err();
+		assert_eq!((error.function_index, error.instruction_index, &error.error), (Some(0), Some(1), &InstructionError::InvalidOperation { expected: ValueType::I32, found: ValueType::F32 }));
+	}
+
+	#[test]
+	fn validate_sequence_seeded() {
+		let module = Module::new(vec![]);
+		let validator = ModuleValidator::new(&module, Filter::all());
+
+		let stack = validator.validate_sequence(&[Instruction::I32Add, Instruction::I64ExtendUI32], vec![ValueType::I32, ValueType::I32]);
+		assert_eq!(stack, Ok(vec![ValueType::I64]));
+
+		let stack = validator.validate_sequence(&[Instruction::I32Add, Instruction::I32Add], vec![ValueType::I32, ValueType::I32]);
+		assert_eq!(stack, Err(InstructionError::StackUnderflow));
+
+		// the sequence isn't part of a function, so there are no locals to read
+		let stack = validator.validate_sequence(&[Instruction::GetLocal(0)], vec![]);
+		assert_eq!(stack, Err(InstructionError::LocalNotFound));
+
+		// nor results to return
+		let stack = validator.validate_sequence(&[Instruction::Return], vec![ValueType::I32]);
+		assert_eq!(stack, Err(InstructionError::NotInFunction));
+
+		let error = validator.validate_sequence(&[Instruction::I32Add], vec![ValueType::I32, ValueType::F32]).unwrap_err();
+		assert_eq!(error, InstructionError::InvalidOperation { expected: ValueType::I32, found: ValueType::F32 });
+		assert_eq!(error.to_string(), "invalid operation: expected i32, found f32");
+	}
+
+	#[test]
+	fn unreachable_concrete_operand_failure_binary() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     unreachable
+		//     i64.const 0
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x0a, 0x08, 0x01, 0x06, 0x00, 0x00, 0x42, 0x00, 0x6a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// the operand below the i64 may be anything, but the i64 itself was really pushed
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let error = validator.validate().unwrap_err();
+		assert_eq!((error.function_index, error.instruction_index, &error.error), (Some(0), Some(2), &InstructionError::InvalidOperation { expected: ValueType::I32, found: ValueType::I64 }));
+	}
+
+	#[test]
+	fn numeric_filter_skips_control_binary() {
+		// WAST:
+		// (module
+		//   (func $f (result i32)
+		//     i32.const 1)
+		//   (func (param i32) (result i32)
+		//     call $f
+		//     i32.const 1
+		//     i32.add
+		//     block (result i32)
+		//       i32.const 2
+		//       local.get 0
+		//       br_if 0
+		//       drop
+		//       i32.const 3
+		//     end
+		//     i32.add))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0a, 0x02, 0x60, 0x00, 0x01, 0x7f, 0x60,
+			0x01, 0x7f, 0x01, 0x7f, 0x03, 0x03, 0x02, 0x00, 0x01, 0x0a, 0x1b, 0x02, 0x04, 0x00, 0x41, 0x01,
+			0x0b, 0x14, 0x00, 0x10, 0x00, 0x41, 0x01, 0x6a, 0x02, 0x7f, 0x41, 0x02, 0x20, 0x00, 0x0d, 0x00,
+			0x1a, 0x41, 0x03, 0x0b, 0x6a, 0x0b, 0x00, 0x0b, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x01, 0x04, 0x01,
+			0x00, 0x01, 0x66
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// skipped calls and blocks still pop and push what they would at runtime
+		assert!(ModuleValidator::new(&module, Filter::all()).validate().unwrap());
+		assert!(ModuleValidator::new(&module, Filter::NUMERIC).validate().unwrap());
+	}
+
+	#[test]
+	fn module_error_location_binary() {
+		// WAST:
+		// (module
+		//   (func (result i32)
+		//     i32.const 1)
+		//   (start 0))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, 0x03,
+			0x02, 0x01, 0x00, 0x08, 0x01, 0x00, 0x0a, 0x06, 0x01, 0x04, 0x00, 0x41, 0x01, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		// the start function's type is the problem, not any of its instructions
+		let error = ModuleValidator::new(&module, Filter::all()).validate().unwrap_err();
+		assert_eq!((error.function_index, error.instruction_index), (None, None));
+		assert_eq!(error.to_string(), "module: start function 0 must take no parameters and return no results");
+	}
+
+	#[test]
+	fn conversion_br_table_binary_category_counts() {
+		// WAST:
+		// (module
+		//   (func (param i64) (result f64)
+		//     block
+		//       get_local 0
+		//       i32.wrap/i64
+		//       br_table 0 0
+		//     end
 		//     get_local 0
-		//     get_local 1
-		//     i32.add)
-		//   (export "addTwo" (func $addTwo)))
-		let module = deserialize_file("./src/wasm_binaries/test.wasm").unwrap();
-		let mut validator = ModuleValidator::new(&module, NumericInstructions);
-		let is_valid = validator.validate().unwrap();
-		assert!(is_valid)
+		//     f64.convert_s/i64
+		//     f32.demote/f64
+		//     f64.promote/f32))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7e, 0x01, 0x7c,
+			0x03, 0x02, 0x01, 0x00, 0x0a, 0x13, 0x01, 0x11, 0x00, 0x02, 0x40, 0x20, 0x00, 0xa7, 0x0e, 0x01,
+			0x00, 0x00, 0x0b, 0x20, 0x00, 0xb9, 0xb6, 0xbb, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
+
+		let validator = ModuleValidator::new(&module, Filter::all());
+		let report = validator.validate_report();
+		assert!(report.valid);
+		assert_eq!(report.category_counts.get("CONVERSION_INST"), Some(&4));
+		// block, br_table and the two ends
+		assert_eq!(report.category_counts.get("CONTROL_INST"), Some(&4));
+		assert_eq!(report.category_counts.get("GET_INST"), Some(&2));
+		assert_eq!(report.instructions_checked, report.category_counts.values().sum::<usize>());
 	}
 
 	#[test]
-	fn complex_instruction_binary() {
+	fn local_groups_binary() {
 		// WAST:
 		// (module
-		//   (type $t0 (func (param i32 i32) (result i32)))
-		//   (func $_Z4multii (export "_Z4multii") (type $t0) (param $p0 i32) (param $p1 i32) (result i32)
-		//     (i32.mul
-		//       (get_local $p1)
-		//       (get_local $p0)))
-		//   (func $_Z3addii (export "_Z3addii") (type $t0) (param $p0 i32) (param $p1 i32) (result i32)
-		//     (i32.add
-		//       (get_local $p1)
-		//       (get_local $p0)))
-		//   (func $_Z6divideii (export "_Z6divideii") (type $t0) (param $p0 i32) (param $p1 i32) (result i32)
-		//     (i32.div_s
-		//       (get_local $p0)
-		//       (get_local $p1)))
-		//   (table $T0 0 anyfunc)
-		//   (memory $memory (export "memory") 1))
+		//   (func (param i64) (result f64) (local i32 i32 f32 f64)
+		//     get_local 0
+		//     i64.eqz
+		//     get_local 2
+		//     i32.add
+		//     drop
+		//     get_local 3
+		//     f32.neg
+		//     drop
+		//     get_local 4)
+		//   (func (param i64) (local i32 i32 f32)
+		//     get_local 4
+		//     drop))
+		let wasm: Vec<u8> = vec![
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0a, 0x02, 0x60, 0x01, 0x7e, 0x01, 0x7c,
+			0x60, 0x01, 0x7e, 0x00, 0x03, 0x03, 0x02, 0x00, 0x01, 0x0a, 0x21, 0x02, 0x15, 0x03, 0x02, 0x7f,
+			0x01, 0x7d, 0x01, 0x7c, 0x20, 0x00, 0x50, 0x20, 0x02, 0x6a, 0x1a, 0x20, 0x03, 0x8c, 0x1a, 0x20,
+			0x04, 0x0b, 0x09, 0x02, 0x02, 0x7f, 0x01, 0x7d, 0x20, 0x04, 0x1a, 0x0b
+		];
+
+		let module = deserialize_buffer::<Module>(&wasm).unwrap();
 
+		// the parameter and every declared group are looked up on both sides of their boundaries
+		let validator = ModuleValidator::new(&module, Filter::all());
+		assert_eq!(validator.validate_function(0), Ok(true));
+		assert_eq!(validator.validate_function(1), Err(InstructionError::LocalNotFound));
+	}
+
+	#[test]
+	fn no_float_rule_resolved_types() {
+		// WAST:
+		// (module
+		//   (type (func (param f32)))
+		//   (type (func (result f64)))
+		//   (table 1 anyfunc)
+		//   (global (mut f64) (f64.const 0))
+		//   (func (type 0) (local f32)
+		//     get_local 0
+		//     tee_local 1
+		//     set_local 1)
+		//   (func
+		//     get_global 0
+		//     drop)
+		//   (func (result f64)
+		//     call 3)
+		//   (func (result f64)
+		//     i32.const 0
+		//     call_indirect (type 1)))
 		let wasm: Vec<u8> = vec![
-			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7f, 0x7f, 0x01,
-			0x7f, 0x03, 0x04, 0x03, 0x00, 0x00, 0x00, 0x04, 0x04, 0x01, 0x70, 0x00, 0x00, 0x05, 0x03, 0x01,
-			0x00, 0x01, 0x07, 0x2f, 0x04, 0x09, 0x5f, 0x5a, 0x34, 0x6d, 0x75, 0x6c, 0x74, 0x69, 0x69, 0x00,
-			0x00, 0x08, 0x5f, 0x5a, 0x33, 0x61, 0x64, 0x64, 0x69, 0x69, 0x00, 0x01, 0x0b, 0x5f, 0x5a, 0x36,
-			0x64, 0x69, 0x76, 0x69, 0x64, 0x65, 0x69, 0x69, 0x00, 0x02, 0x06, 0x6d, 0x65, 0x6d, 0x6f, 0x72,
-			0x79, 0x02, 0x00, 0x0a, 0x19, 0x03, 0x07, 0x00, 0x20, 0x01, 0x20, 0x00, 0x6c, 0x0b, 0x07, 0x00,
-			0x20, 0x01, 0x20, 0x00, 0x6a, 0x0b, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6d, 0x0b, 0x00, 0x4b,
-			0x04, 0x6e, 0x61, 0x6d, 0x65, 0x01, 0x23, 0x03, 0x00, 0x09, 0x5f, 0x5a, 0x34, 0x6d, 0x75, 0x6c,
-			0x74, 0x69, 0x69, 0x01, 0x08, 0x5f, 0x5a, 0x33, 0x61, 0x64, 0x64, 0x69, 0x69, 0x02, 0x0b, 0x5f,
-			0x5a, 0x36, 0x64, 0x69, 0x76, 0x69, 0x64, 0x65, 0x69, 0x69, 0x02, 0x1f, 0x03, 0x00, 0x02, 0x00,
-			0x02, 0x70, 0x30, 0x01, 0x02, 0x70, 0x31, 0x01, 0x02, 0x00, 0x02, 0x70, 0x30, 0x01, 0x02, 0x70,
-			0x31, 0x02, 0x02, 0x00, 0x02, 0x70, 0x30, 0x01, 0x02, 0x70, 0x31
+			0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0c, 0x03, 0x60, 0x01, 0x7d, 0x00, 0x60,
+			0x00, 0x01, 0x7c, 0x60, 0x00, 0x00, 0x03, 0x05, 0x04, 0x00, 0x02, 0x01, 0x01, 0x04, 0x04, 0x01,
+			0x70, 0x00, 0x01, 0x06, 0x0d, 0x01, 0x7c, 0x01, 0x44, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			0x00, 0x0b, 0x0a, 0x1f, 0x04, 0x0a, 0x01, 0x01, 0x7d, 0x20, 0x00, 0x22, 0x01, 0x21, 0x01, 0x0b,
+			0x05, 0x00, 0x23, 0x00, 0x1a, 0x0b, 0x04, 0x00, 0x10, 0x03, 0x0b, 0x07, 0x00, 0x41, 0x00, 0x11,
+			0x01, 0x00, 0x0b
 		];
 
 		let module = deserialize_buffer::<Module>(&wasm).unwrap();
 
-		let mut validator = ModuleValidator::new(&module, NumericInstructions);
-		let is_valid = validator.validate().unwrap();
-		assert!(is_valid)
+		let mut validator = ModuleValidator::new(&module, Filter::all());
+		assert!(validator.validate().unwrap());
+		validator.add_rule(Box::new(NoFloat));
+
+		// none of these have a float signature, their operands only get a type from the local, global or callee.
+		// An error leaves the rest of its block unreachable, so a float is only forbidden where it first shows up.
+		let forbidden: Vec<_> = validator.errors().map(|error| (error.function_index, error.error.to_string())).collect();
+		assert_eq!(forbidden, vec![
+			(Some(0), "get_local 0 is not allowed".to_string()),
+			(Some(0), "tee_local 1 is not allowed".to_string()),
+			(Some(1), "get_global 0 is not allowed".to_string()),
+			(Some(2), "call 3 is not allowed".to_string()),
+			(Some(3), "call_indirect 1 is not allowed".to_string()),
+		]);
+
+		// floats already on the stack are forbidden when they are taken, and blocks by their type
+		let forbid = |instruction: Instruction, initial: Vec<ValueType>| validator.validate_sequence(&[instruction], initial).unwrap_err().to_string();
+		assert_eq!(forbid(Instruction::SetGlobal(0), vec![ValueType::F64]), "set_global 0 is not allowed");
+		assert_eq!(forbid(Instruction::Call(0), vec![ValueType::F32]), "call 0 is not allowed");
+		assert_eq!(forbid(Instruction::Select, vec![ValueType::F32, ValueType::F32, ValueType::I32]), "select is not allowed");
+		assert_eq!(forbid(Instruction::Drop, vec![ValueType::F64]), "drop is not allowed");
+		assert_eq!(forbid(Instruction::Block(BlockType::Value(ValueType::F32)), vec![]), "block f32 is not allowed");
+		assert_eq!(forbid(Instruction::If(BlockType::Value(ValueType::F64)), vec![ValueType::I32]), "if f64 is not allowed");
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,73 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::process;
+
+use parity_wasm::deserialize_buffer;
+use wasm_chisel::{Filter, ModuleValidatorBuilder};
+
+const USAGE: &str = "usage: chisel validate <path.wasm> [--filter numeric|all]";
+
+/// Exit code for a module that failed validation
+const INVALID: i32 = 1;
+/// Exit code for bad arguments or a module that couldn't be read
+const ERROR: i32 = 2;
+
+fn main() {
+	let args: Vec<String> = env::args().skip(1).collect();
+	let (path, filter) = match parse_args(&args) {
+		Some(parsed) => parsed,
+		None => {
+			eprintln!("{}", USAGE);
+			process::exit(ERROR)
+		}
+	};
+
+	let bytes = match fs::read(path) {
+		Ok(bytes) => bytes,
+		Err(e) => {
+			eprintln!("{}: failed to read module: {}", path, e);
+			process::exit(ERROR)
+		}
+	};
+	let module = match deserialize_buffer(&bytes) {
+		Ok(module) => module,
+		Err(e) => {
+			eprintln!("{}: failed to read module: {}", path, e);
+			process::exit(ERROR)
+		}
+	};
+
+	// the source bytes give the real offsets of the errors, whatever encoding the module used
+	let validator = ModuleValidatorBuilder::new(&module).filter(filter).source(&bytes).build();
+	let valid = match validator.report_to(&mut io::stdout()) {
+		Ok(valid) => valid,
+		Err(e) => {
+			eprintln!("{}: failed to write report: {}", path, e);
+			process::exit(ERROR)
+		}
+	};
+
+	if valid {
+		println!("{}: valid", path);
+	} else {
+		println!("{}: invalid", path);
+		process::exit(INVALID)
+	}
+}
+
+/// Reads `validate <path> [--filter numeric|all]`, returning `None` if the arguments don't fit
+fn parse_args(args: &[String]) -> Option<(&str, Filter)> {
+	match args {
+		[command, path] if command == "validate" => Some((path, Filter::all())),
+		[command, path, flag, filter] if command == "validate" && flag == "--filter" => {
+			let filter = match filter.as_str() {
+				"numeric" => Filter::NUMERIC,
+				"all" => Filter::all(),
+				_ => return None,
+			};
+			Some((path, filter))
+		},
+		_ => None,
+	}
+}
@@ -0,0 +1,55 @@
+use crate::errors::LocatedError;
+use std::collections::HashMap;
+
+/// The outcome of validating a module and every function in it
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ValidationReport {
+	/// Whether the module and every function in it are valid
+	pub valid: bool,
+	/// The problem found outside of the function bodies, if there is one
+	pub module_error: Option<LocatedError>,
+	/// The number of function bodies that were checked
+	pub functions_checked: usize,
+	/// The number of instructions checked across all function bodies
+	pub instructions_checked: usize,
+	/// One report per function body, in the order they appear in the code section
+	pub per_function: Vec<FunctionReport>,
+	/// How many of the checked instructions fell into each classification, keyed by its name
+	pub category_counts: HashMap<String, usize>,
+}
+
+/// The outcome of validating a single function body
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FunctionReport {
+	/// The index of the function's body in the code section
+	pub function_index: usize,
+	/// Whether the function is valid
+	pub valid: bool,
+	/// Every error found in the function, empty if it is valid
+	pub errors: Vec<LocatedError>,
+	/// The positions of instructions that can never run, if the validator was asked to warn about them
+	pub unreachable: Vec<usize>,
+}
+
+impl ValidationReport {
+	/// Builds a report from the module-level check and the reports of each function
+	pub(crate) fn new(module_error: Option<LocatedError>, per_function: Vec<FunctionReport>, instructions_checked: usize, category_counts: HashMap<String, usize>) -> Self {
+		ValidationReport {
+			valid: module_error.is_none() && per_function.iter().all(|function| function.valid),
+			module_error,
+			functions_checked: per_function.len(),
+			instructions_checked,
+			per_function,
+			category_counts,
+		}
+	}
+
+	/// Takes every error out of the report, the module-level one first and then in the order the functions appear
+	pub fn into_errors(self) -> Vec<LocatedError> {
+		self.module_error.into_iter()
+			.chain(self.per_function.into_iter().flat_map(|function| function.errors))
+			.collect()
+	}
+}
@@ -0,0 +1,53 @@
+use crate::errors::InstructionError;
+use parity_wasm::elements::{BlockType, Instruction, ValueType};
+
+/// An extra check run on every instruction, on top of the validator's own type checking.
+/// Rules let callers enforce policies of their own, like rejecting whole families of instructions.
+pub trait InstructionRule {
+	/// Checks `instruction` given the operand stack just before it runs, bottom first
+	fn check(&self, instruction: &Instruction, stack: &[ValueType]) -> Result<(), InstructionError>;
+
+	/// Checks `instruction` once it has run, given the operands it took and the values it left, bottom first.
+	/// Unlike a signature, these are the types the instruction resolved to, e.g. those of a local or a callee.
+	fn check_effect(&self, _instruction: &Instruction, _popped: &[ValueType], _pushed: &[ValueType]) -> Result<(), InstructionError> {
+		Ok(())
+	}
+}
+
+/// Rejects every instruction that takes or produces a floating-point value.
+/// Deterministic environments forbid these, since float results can differ between machines.
+pub struct NoFloat;
+
+fn is_float(value_type: &ValueType) -> bool {
+	matches!(value_type, ValueType::F32 | ValueType::F64)
+}
+
+impl InstructionRule for NoFloat {
+	fn check(&self, instruction: &Instruction, _stack: &[ValueType]) -> Result<(), InstructionError> {
+		// the signature catches float operators even in unreachable code, where no operands are popped
+		let has_float_signature = crate::get_instruction_signature(instruction)
+			.is_some_and(|signature| signature.pop.iter().chain(&signature.push).any(is_float));
+		let has_float_block = match instruction {
+			Instruction::Block(BlockType::Value(value_type))
+			| Instruction::Loop(BlockType::Value(value_type))
+			| Instruction::If(BlockType::Value(value_type)) => is_float(value_type),
+			_ => false,
+		};
+		if has_float_signature || has_float_block {
+			return Err(InstructionError::ForbiddenInstruction(instruction.to_string()))
+		}
+		Ok(())
+	}
+
+	fn check_effect(&self, instruction: &Instruction, popped: &[ValueType], pushed: &[ValueType]) -> Result<(), InstructionError> {
+		// a block's values were already reported by whatever made them, or by the block's own type
+		if matches!(instruction, Instruction::End | Instruction::Else) {
+			return Ok(())
+		}
+		// locals, globals, `select` and calls only show their float operands here
+		if popped.iter().chain(pushed).any(is_float) {
+			return Err(InstructionError::ForbiddenInstruction(instruction.to_string()))
+		}
+		Ok(())
+	}
+}
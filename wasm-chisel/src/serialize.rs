@@ -0,0 +1,17 @@
+use crate::value_type_name;
+use parity_wasm::elements::ValueType;
+use serde::ser::{SerializeSeq, Serializer};
+
+/// Serializes a value type by its text format name
+pub fn value_type<S: Serializer>(value_type: &ValueType, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(value_type_name(*value_type))
+}
+
+/// Serializes value types by their text format names, since parity-wasm's `ValueType` isn't `Serialize`
+pub fn value_types<S: Serializer>(value_types: &[ValueType], serializer: S) -> Result<S::Ok, S::Error> {
+	let mut seq = serializer.serialize_seq(Some(value_types.len()))?;
+	for value_type in value_types {
+		seq.serialize_element(value_type_name(*value_type))?;
+	}
+	seq.end()
+}
@@ -0,0 +1,40 @@
+use std::env;
+use std::fs;
+use std::process::Command;
+
+fn chisel(args: &[&str]) -> std::process::Output {
+	Command::new(env!("CARGO_BIN_EXE_chisel")).args(args).output().unwrap()
+}
+
+#[test]
+fn valid_module() {
+	let output = chisel(&["validate", "./src/wasm_binaries/test.wasm"]);
+	assert_eq!(output.status.code(), Some(0));
+	assert!(String::from_utf8_lossy(&output.stdout).contains(": valid"));
+}
+
+#[test]
+fn invalid_module() {
+	// WAST:
+	// (module
+	//   (func (param f64 i32) (result i32)
+	//     get_local 0
+	//     get_local 1
+	//     i32.add))
+	let wasm: Vec<u8> = vec![
+		0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x07, 0x01, 0x60, 0x02, 0x7c, 0x7f, 0x01,
+		0x7f, 0x03, 0x02, 0x01, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b
+	];
+	let path = env::temp_dir().join("chisel_invalid_module.wasm");
+	fs::write(&path, &wasm).unwrap();
+
+	let output = chisel(&["validate", path.to_str().unwrap(), "--filter", "numeric"]);
+	assert_eq!(output.status.code(), Some(1));
+	assert!(String::from_utf8_lossy(&output.stdout).contains("function 0, instruction 2"));
+}
+
+#[test]
+fn bad_arguments() {
+	let output = chisel(&["validate"]);
+	assert_eq!(output.status.code(), Some(2));
+}